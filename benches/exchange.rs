@@ -0,0 +1,70 @@
+//! Measures the `__dlpack_c_exchange_api__` lookup that every versioned
+//! extraction starts with, with and without the per-type cache, for a type
+//! that publishes a table and one that does not, and the full extraction of a
+//! `DlpackTensor` through the exchange fast path.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use dlpark::Foreign;
+use dlpark::Local;
+use dlpark::ffi::DLManagedTensorVersioned;
+use dlpark::python::{PyDlpackTensor, exchange::DlpackExchangeApiRef};
+use pyo3::conversion::FromPyObject;
+use pyo3::{Py, Python};
+
+fn bench_exchange_lookup(c: &mut Criterion) {
+    Python::initialize();
+    Python::attach(|py| {
+        let tensor = Local::<DLManagedTensorVersioned>::zeros::<f32>(&[2, 3]).unwrap();
+        let object = Py::new(py, PyDlpackTensor::new(tensor))
+            .unwrap()
+            .into_bound(py)
+            .into_any();
+        let mut group = c.benchmark_group("exchange_api");
+
+        group.bench_function("lookup_uncached", |b| {
+            b.iter(|| {
+                std::hint::black_box(
+                    DlpackExchangeApiRef::from_object_uncached(object.as_borrowed()).unwrap(),
+                );
+            });
+        });
+
+        group.bench_function("lookup_cached", |b| {
+            b.iter(|| {
+                std::hint::black_box(
+                    DlpackExchangeApiRef::from_object(object.as_borrowed()).unwrap(),
+                );
+            });
+        });
+
+        let plain = py.eval(c"object()", None, None).unwrap();
+        group.bench_function("missing_uncached", |b| {
+            b.iter(|| {
+                std::hint::black_box(
+                    DlpackExchangeApiRef::from_object_uncached(plain.as_borrowed()).unwrap(),
+                );
+            });
+        });
+
+        group.bench_function("missing_cached", |b| {
+            b.iter(|| {
+                std::hint::black_box(
+                    DlpackExchangeApiRef::from_object(plain.as_borrowed()).unwrap(),
+                );
+            });
+        });
+
+        group.bench_function("extract", |b| {
+            b.iter(|| {
+                std::hint::black_box(
+                    Foreign::<DLManagedTensorVersioned>::extract(object.as_borrowed()).unwrap(),
+                );
+            });
+        });
+
+        group.finish();
+    });
+}
+
+criterion_group!(benches, bench_exchange_lookup);
+criterion_main!(benches);
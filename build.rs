@@ -0,0 +1,7 @@
+//! Emits PyO3's interpreter cfgs, such as `Py_LIMITED_API` and `Py_3_11`, for
+//! the Python bindings.
+
+fn main() {
+    #[cfg(feature = "pyo3")]
+    pyo3_build_config::use_pyo3_cfgs();
+}
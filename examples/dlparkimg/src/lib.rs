@@ -1,15 +1,14 @@
 use dlpark::{allocation::fixed, ffi::DLManagedTensorVersioned, Foreign, Local, TryFromDlpack};
 use image::{ImageBuffer, Rgb};
-use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::exceptions::PyIOError;
 use pyo3::prelude::*;
 
 #[pyfunction]
 fn read_image(filename: &str) -> PyResult<Local<DLManagedTensorVersioned>> {
     let img = image::open(filename).map_err(|err| PyIOError::new_err(err.to_string()))?;
     let rgb_img = img.to_rgb8();
-    let initialized: fixed::Initialized<DLManagedTensorVersioned, 3> = Box::new(rgb_img)
-        .try_into()
-        .map_err(|err: dlpark::metadata::Error| PyValueError::new_err(err.to_string()))?;
+    let initialized: fixed::Initialized<DLManagedTensorVersioned, 3> =
+        Box::new(rgb_img).try_into()?;
     Ok(unsafe { initialized.finish() })
 }
 
@@ -17,8 +16,7 @@ fn read_image(filename: &str) -> PyResult<Local<DLManagedTensorVersioned>> {
 fn write_image(filename: &str, tensor: Foreign<DLManagedTensorVersioned>) -> PyResult<()> {
     // SAFETY: this extension accepts tensors through the Python DLPack
     // protocol and relies on the producer to provide a valid descriptor.
-    let rgb_img: ImageBuffer<Rgb<u8>, _> = unsafe { ImageBuffer::try_from_dlpack(&tensor) }
-        .map_err(|err: dlpark::interop::image::Error| PyValueError::new_err(err.to_string()))?;
+    let rgb_img: ImageBuffer<Rgb<u8>, _> = unsafe { ImageBuffer::try_from_dlpack(&tensor) }?;
     rgb_img
         .save(filename)
         .map_err(|err| PyIOError::new_err(err.to_string()))
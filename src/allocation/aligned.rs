@@ -0,0 +1,454 @@
+//! Owned element buffers with a guaranteed base alignment.
+
+use super::{Error, dynamic, fixed, vec::Elements};
+use crate::{DlpackElement, DlpackFlags, Local, ManagedTensorBase, metadata};
+use std::{alloc::Layout, io, mem::MaybeUninit, num::NonZeroUsize, ptr::NonNull};
+
+/// The base alignment the DLPack header asks producers to provide.
+pub const DEFAULT_ALIGNMENT: usize = 256;
+
+/// Bytes [`AlignedBuffer::read_exact_from`] allocates before any data has
+/// arrived.
+const READ_CHUNK: usize = 1 << 20;
+
+/// An owned, fixed-length buffer of `T` whose data pointer is aligned to a
+/// chosen power of two, [`DEFAULT_ALIGNMENT`] unless stated otherwise.
+///
+/// `Vec<T>` only guarantees `align_of::<T>()`. Exporting an `AlignedBuffer`
+/// instead gives consumers such as SIMD kernels the 256-byte alignment the
+/// DLPack header recommends. It derefs to `[T]` and exports without copying
+/// through [`Local::from_aligned`] or
+/// [`dynamic::Initialized::from_aligned`].
+pub struct AlignedBuffer<T> {
+    ptr: NonNull<T>,
+    len: usize,
+    /// Allocated element count; `len` only differs after a short iterator.
+    capacity: usize,
+    align: usize,
+}
+
+// SAFETY: the buffer uniquely owns its elements, like `Box<[T]>`.
+unsafe impl<T: Send> Send for AlignedBuffer<T> {}
+unsafe impl<T: Sync> Sync for AlignedBuffer<T> {}
+
+impl<T> AlignedBuffer<T> {
+    /// Returns the base alignment in bytes.
+    pub fn alignment(&self) -> usize {
+        self.align
+    }
+
+    fn layout(capacity: usize, align: usize) -> Result<Layout, Error> {
+        if !align.is_power_of_two() || align < align_of::<T>() {
+            return Err(Error::InvalidAlignment {
+                align,
+                min: align_of::<T>(),
+            });
+        }
+        let size = size_of::<T>()
+            .checked_mul(capacity)
+            .ok_or(Error::LayoutOverflow)?;
+        Layout::from_size_align(size, align).map_err(|_| Error::LayoutOverflow)
+    }
+
+    fn allocate(len: usize, align: usize, zeroed: bool) -> Result<Self, Error> {
+        let layout = Self::layout(len, align)?;
+        let ptr = match NonZeroUsize::new(layout.size()) {
+            // Zero-sized buffers are never dereferenced; any aligned address
+            // will do.
+            None => NonNull::without_provenance(NonZeroUsize::new(align).unwrap()),
+            Some(_) => {
+                let raw = if zeroed {
+                    unsafe { std::alloc::alloc_zeroed(layout) }
+                } else {
+                    unsafe { std::alloc::alloc(layout) }
+                };
+                NonNull::new(raw.cast()).unwrap_or_else(|| std::alloc::handle_alloc_error(layout))
+            }
+        };
+        Ok(Self {
+            ptr,
+            len,
+            capacity: len,
+            align,
+        })
+    }
+}
+
+impl<T: DlpackElement> AlignedBuffer<T> {
+    /// Allocates `len` zeroed elements at [`DEFAULT_ALIGNMENT`].
+    pub fn zeroed(len: usize) -> Result<Self, Error> {
+        Self::zeroed_with_alignment(len, DEFAULT_ALIGNMENT)
+    }
+
+    /// Allocates `len` zeroed elements at `align` bytes.
+    ///
+    /// `align` must be a power of two no smaller than `align_of::<T>()`.
+    pub fn zeroed_with_alignment(len: usize, align: usize) -> Result<Self, Error> {
+        // All-zero bytes are a valid value of every DLPack element type.
+        Self::allocate(len, align, true)
+    }
+
+    /// Allocates `len` uninitialized elements at [`DEFAULT_ALIGNMENT`].
+    ///
+    /// Fill the buffer, then call [`AlignedBuffer::assume_init`].
+    pub fn uninit(len: usize) -> Result<AlignedBuffer<MaybeUninit<T>>, Error> {
+        Self::uninit_with_alignment(len, DEFAULT_ALIGNMENT)
+    }
+
+    /// Allocates `len` uninitialized elements at `align` bytes.
+    pub fn uninit_with_alignment(
+        len: usize,
+        align: usize,
+    ) -> Result<AlignedBuffer<MaybeUninit<T>>, Error> {
+        AlignedBuffer::allocate(len, align, false)
+    }
+
+    /// Collects an exact-size iterator at [`DEFAULT_ALIGNMENT`].
+    pub fn from_exact_iter<I>(iter: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        Self::from_exact_iter_with_alignment(iter, DEFAULT_ALIGNMENT)
+    }
+
+    /// Collects an exact-size iterator at `align` bytes.
+    ///
+    /// The buffer is sized from the iterator's reported length; surplus items
+    /// are ignored, and if it yields fewer, only those are kept.
+    pub fn from_exact_iter_with_alignment<I>(iter: I, align: usize) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let mut buffer = Self::uninit_with_alignment(iter.len(), align)?;
+        let mut written = 0;
+        for (slot, value) in buffer.iter_mut().zip(iter) {
+            slot.write(value);
+            written += 1;
+        }
+        buffer.len = written;
+        // SAFETY: the first `written` elements were initialized above.
+        Ok(unsafe { buffer.assume_init() })
+    }
+
+    /// Copies `values` into a new buffer at [`DEFAULT_ALIGNMENT`].
+    pub fn from_slice(values: &[T]) -> Result<Self, Error>
+    where
+        T: Copy,
+    {
+        Self::from_exact_iter(values.iter().copied())
+    }
+}
+
+impl AlignedBuffer<u8> {
+    /// Reads exactly `len` bytes from `reader` into a new buffer at
+    /// [`DEFAULT_ALIGNMENT`].
+    ///
+    /// The buffer starts small and at most doubles with the bytes already
+    /// read, so a `len` taken from an untrusted header cannot allocate much
+    /// more than the input holds. A short input fails with
+    /// [`io::ErrorKind::UnexpectedEof`].
+    pub(crate) fn read_exact_from<R: io::Read>(mut reader: R, len: usize) -> io::Result<Self> {
+        let mut buffer = Self::zeroed(len.min(READ_CHUNK)).map_err(io::Error::other)?;
+        let mut filled = 0;
+        loop {
+            reader.read_exact(&mut buffer[filled..])?;
+            filled = buffer.len;
+            if filled == len {
+                return Ok(buffer);
+            }
+            buffer
+                .grow_zeroed(len.min(filled.saturating_mul(2)))
+                .map_err(io::Error::other)?;
+        }
+    }
+
+    /// Grows a non-empty buffer to `len` bytes, zeroing the new tail.
+    fn grow_zeroed(&mut self, len: usize) -> Result<(), Error> {
+        let old = Self::layout(self.capacity, self.align)?;
+        let new = Self::layout(len, self.align)?;
+        debug_assert!(old.size() != 0 && len >= self.len);
+        // SAFETY: the buffer was allocated with `old`, which is non-empty,
+        // and `new` has the same alignment.
+        let raw = unsafe { std::alloc::realloc(self.ptr.as_ptr(), old, new.size()) };
+        self.ptr = NonNull::new(raw).unwrap_or_else(|| std::alloc::handle_alloc_error(new));
+        // SAFETY: the new tail lies within the reallocated block.
+        unsafe {
+            self.ptr
+                .as_ptr()
+                .add(self.len)
+                .write_bytes(0, len - self.len)
+        };
+        self.len = len;
+        self.capacity = len;
+        Ok(())
+    }
+}
+
+impl<T> AlignedBuffer<MaybeUninit<T>> {
+    /// Converts to an initialized buffer.
+    ///
+    /// # Safety
+    ///
+    /// Every element must have been initialized.
+    pub unsafe fn assume_init(self) -> AlignedBuffer<T> {
+        let this = std::mem::ManuallyDrop::new(self);
+        AlignedBuffer {
+            ptr: this.ptr.cast(),
+            len: this.len,
+            capacity: this.capacity,
+            align: this.align,
+        }
+    }
+}
+
+impl<T> std::ops::Deref for AlignedBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> std::ops::DerefMut for AlignedBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T> Drop for AlignedBuffer<T> {
+    fn drop(&mut self) {
+        unsafe { std::ptr::drop_in_place(&mut **self as *mut [T]) };
+        let layout =
+            Self::layout(self.capacity, self.align).expect("layout was validated on allocation");
+        if layout.size() != 0 {
+            unsafe { std::alloc::dealloc(self.ptr.as_ptr().cast(), layout) };
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for AlignedBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuffer")
+            .field("align", &self.align)
+            .field("data", &&**self)
+            .finish()
+    }
+}
+
+impl<T: Send + 'static> Elements<T> for AlignedBuffer<T> {
+    type Context = Box<Self>;
+    const FLAGS: DlpackFlags = DlpackFlags::IS_COPIED;
+
+    fn as_ptr(&self) -> *const T {
+        self.ptr.as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self)
+    }
+}
+
+impl<M: ManagedTensorBase> dynamic::Initialized<M> {
+    /// Exports an aligned buffer as a compact row-major CPU tensor without
+    /// copying.
+    ///
+    /// The buffer becomes the manager context and the tensor starts with
+    /// [`DlpackFlags::IS_COPIED`]. The buffer may hold more elements than
+    /// `shape` addresses.
+    pub fn from_aligned<T>(data: AlignedBuffer<T>, shape: &[i64]) -> Result<Self, metadata::Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_aligned`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_aligned_with_strides<T>(
+        data: AlignedBuffer<T>,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, metadata::Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+}
+
+impl<M: ManagedTensorBase, const N: usize> fixed::Initialized<M, N> {
+    /// Exports an aligned buffer as a compact row-major fixed-rank CPU tensor
+    /// without copying. See [`dynamic::Initialized::from_aligned`].
+    pub fn from_aligned<T>(data: AlignedBuffer<T>, shape: [i64; N]) -> Result<Self, metadata::Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+}
+
+/// Exports the buffer as a one-dimensional tensor of its full length.
+impl<T, M> TryFrom<AlignedBuffer<T>> for dynamic::Initialized<M>
+where
+    T: DlpackElement + Send,
+    M: ManagedTensorBase,
+{
+    type Error = metadata::Error;
+
+    fn try_from(data: AlignedBuffer<T>) -> Result<Self, Self::Error> {
+        let len =
+            i64::try_from(data.len).map_err(|_| metadata::Error::ShapeValueOverflow { axis: 0 })?;
+        Self::from_aligned(data, &[len])
+    }
+}
+
+impl<M: ManagedTensorBase> Local<M> {
+    /// Exports an aligned buffer as a compact row-major CPU tensor without
+    /// copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_aligned`] followed by
+    /// `finish`.
+    pub fn from_aligned<T>(data: AlignedBuffer<T>, shape: &[i64]) -> Result<Self, metadata::Error>
+    where
+        T: DlpackElement + Send,
+    {
+        let initialized = dynamic::Initialized::from_aligned(data, shape)?;
+        // SAFETY: the descriptor addresses only elements of the owned buffer,
+        // which lives until the tensor is dropped.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Returns this tensor unchanged if its element data starts on a
+    /// [`DEFAULT_ALIGNMENT`] boundary, and otherwise a compact copy in an
+    /// [`AlignedBuffer`] with the same shape and dtype.
+    ///
+    /// The copy is a fresh [`DlpackFlags::IS_COPIED`] tensor. Copying requires
+    /// compact CPU data of element type `T`.
+    pub fn ensure_aligned<T>(self) -> Result<Self, crate::Error>
+    where
+        T: DlpackElement + Send + Copy,
+    {
+        // SAFETY: `Local` guarantees valid descriptor metadata.
+        let data = unsafe { self.tensor().offset_data_ptr::<T>() }?;
+        if data.addr().is_multiple_of(DEFAULT_ALIGNMENT) {
+            return Ok(self);
+        }
+
+        let buffer = AlignedBuffer::from_slice(self.cpu_slice::<T>()?)?;
+        Ok(Self::from_aligned(buffer, self.shape()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        allocation::fixed::make_test_tensor,
+        ffi::{DLDataType, DLDevice, DLManagedTensor, DLManagedTensorVersioned},
+    };
+
+    #[test]
+    fn constructors_honor_the_alignment() {
+        let zeroed = AlignedBuffer::<f32>::zeroed(10).unwrap();
+        assert_eq!(zeroed.alignment(), DEFAULT_ALIGNMENT);
+        assert!(zeroed.as_ptr().addr().is_multiple_of(DEFAULT_ALIGNMENT));
+        assert_eq!(&*zeroed, &[0.0; 10]);
+
+        let mut uninit = AlignedBuffer::<u16>::uninit_with_alignment(3, 64).unwrap();
+        for (index, slot) in uninit.iter_mut().enumerate() {
+            slot.write(index as u16);
+        }
+        let filled = unsafe { uninit.assume_init() };
+        assert!(filled.as_ptr().addr().is_multiple_of(64));
+        assert_eq!(&*filled, &[0, 1, 2]);
+
+        let collected = AlignedBuffer::from_exact_iter((0..5).map(|v| v as f64)).unwrap();
+        assert_eq!(&*collected, &[0.0, 1.0, 2.0, 3.0, 4.0]);
+
+        let empty = AlignedBuffer::<i8>::zeroed(0).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn invalid_alignments_are_rejected() {
+        assert!(matches!(
+            AlignedBuffer::<u8>::zeroed_with_alignment(4, 48),
+            Err(Error::InvalidAlignment { align: 48, .. })
+        ));
+        assert!(matches!(
+            AlignedBuffer::<f64>::zeroed_with_alignment(4, 4),
+            Err(Error::InvalidAlignment { align: 4, min: 8 })
+        ));
+        assert!(matches!(
+            AlignedBuffer::<u32>::zeroed(usize::MAX),
+            Err(Error::LayoutOverflow)
+        ));
+    }
+
+    #[test]
+    fn reads_grow_with_the_input_rather_than_the_requested_length() {
+        let bytes: Vec<u8> = (0..3 * READ_CHUNK + 5).map(|i| i as u8).collect();
+        let read = AlignedBuffer::read_exact_from(&bytes[..], bytes.len()).unwrap();
+        assert!(read.as_ptr().addr().is_multiple_of(DEFAULT_ALIGNMENT));
+        assert_eq!(&*read, &bytes[..]);
+
+        let empty = AlignedBuffer::read_exact_from(&[][..], 0).unwrap();
+        assert!(empty.is_empty());
+
+        let claimed = usize::MAX / 2;
+        let err = AlignedBuffer::read_exact_from(&bytes[..], claimed).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn export_keeps_alignment_and_allows_mutation() {
+        let buffer = AlignedBuffer::from_slice(&[1_i32, 2, 3, 4, 5, 6]).unwrap();
+        let data_ptr = buffer.as_ptr();
+
+        let mut tensor = Local::<DLManagedTensorVersioned>::from_aligned(buffer, &[2, 3]).unwrap();
+        assert_eq!(tensor.flags(), DlpackFlags::IS_COPIED);
+        assert_eq!(tensor.shape().unwrap(), &[2, 3]);
+        assert_eq!(tensor.cpu_slice::<i32>().unwrap().as_ptr(), data_ptr);
+
+        tensor.cpu_slice_mut::<i32>().unwrap()[0] = 10;
+        assert_eq!(tensor.cpu_slice::<i32>().unwrap()[0], 10);
+
+        let initialized: dynamic::Initialized<DLManagedTensor> =
+            AlignedBuffer::<u8>::zeroed(7).unwrap().try_into().unwrap();
+        let tensor = unsafe { initialized.finish() };
+        assert_eq!(tensor.shape().unwrap(), &[7]);
+    }
+
+    #[test]
+    fn ensure_aligned_copies_only_misaligned_data() {
+        let aligned = AlignedBuffer::from_slice(&[1.0_f32, 2.0, 3.0]).unwrap();
+        let tensor = Local::<DLManagedTensorVersioned>::from_aligned(aligned, &[3]).unwrap();
+        let before = tensor.cpu_slice::<f32>().unwrap().as_ptr();
+        let tensor = tensor.ensure_aligned::<f32>().unwrap();
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap().as_ptr(), before);
+
+        let backing = AlignedBuffer::from_slice(&[0.0_f32, 1.0, 2.0, 3.0, 4.0]).unwrap();
+        let misaligned = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(()),
+            unsafe { backing.as_ptr().add(1) }.cast_mut().cast(),
+            DLDataType::F32,
+            DLDevice::CPU,
+            [2, 2],
+            [2, 1],
+            DlpackFlags::READ_ONLY,
+        );
+        let copy = misaligned.ensure_aligned::<f32>().unwrap();
+        let data = copy.cpu_slice::<f32>().unwrap();
+        assert!(data.as_ptr().addr().is_multiple_of(DEFAULT_ALIGNMENT));
+        assert_eq!(data, &[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(copy.shape().unwrap(), &[2, 2]);
+        assert_eq!(copy.flags(), DlpackFlags::IS_COPIED);
+    }
+}
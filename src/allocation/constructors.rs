@@ -0,0 +1,265 @@
+//! Filled, ranged, identity and scalar CPU tensors.
+//!
+//! Every constructor stores its elements in an [`AlignedBuffer`], so the data
+//! pointer is [`DEFAULT_ALIGNMENT`](super::DEFAULT_ALIGNMENT)-aligned and the
+//! tensor starts with [`DlpackFlags::IS_COPIED`](crate::DlpackFlags).
+
+use super::{AlignedBuffer, fixed};
+use crate::{DlpackElement, Local, ManagedTensorBase, NumericElement, metadata::Error};
+use snafu::Snafu;
+
+/// Errors from [`Local::arange`].
+#[derive(Debug, Snafu)]
+pub enum ArangeError {
+    #[snafu(transparent)]
+    Metadata { source: Error },
+
+    #[snafu(display("arange step must be non-zero and not NaN"))]
+    InvalidStep,
+
+    #[snafu(display("arange bounds must be finite and span at most usize::MAX steps"))]
+    InvalidRange,
+}
+
+/// Returns the element count of a compact tensor with `shape`.
+fn element_count(shape: &[i64]) -> Result<usize, Error> {
+    shape
+        .iter()
+        .enumerate()
+        .try_fold(1usize, |count, (axis, &value)| {
+            let dim = usize::try_from(value).map_err(|_| Error::NegativeShape { axis, value })?;
+            count.checked_mul(dim).ok_or(Error::SpanOverflow)
+        })
+}
+
+impl<M: ManagedTensorBase> Local<M> {
+    /// Creates a compact CPU tensor of `shape` filled with zero bytes.
+    pub fn zeros<T>(shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        let buffer = AlignedBuffer::<T>::zeroed(element_count(shape)?)?;
+        Self::from_aligned(buffer, shape)
+    }
+
+    /// Creates a compact CPU tensor of `shape` filled with ones.
+    pub fn ones<T>(shape: &[i64]) -> Result<Self, Error>
+    where
+        T: NumericElement + Send,
+    {
+        Self::full(shape, T::ONE)
+    }
+
+    /// Creates a compact CPU tensor of `shape` filled with `value`.
+    pub fn full<T>(shape: &[i64], value: T) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Copy,
+    {
+        let len = element_count(shape)?;
+        let buffer = AlignedBuffer::from_exact_iter(std::iter::repeat_n(value, len))?;
+        Self::from_aligned(buffer, shape)
+    }
+
+    /// Creates a one-dimensional CPU tensor of `start + step * i` for every
+    /// `i` whose value lies in `[start, end)`, or in `(end, start]` for a
+    /// negative `step`.
+    ///
+    /// The element count is computed up front by
+    /// [`NumericElement::steps`], and values are computed from the index
+    /// rather than accumulated, so float ranges do not drift. A zero or NaN `step` is
+    /// rejected with [`ArangeError::InvalidStep`], and infinite bounds or
+    /// counts that do not fit in `usize` with [`ArangeError::InvalidRange`].
+    pub fn arange<T>(start: T, end: T, step: T) -> Result<Self, ArangeError>
+    where
+        T: NumericElement + Send,
+    {
+        let ascending = step > T::ZERO;
+        let descending = step < T::ZERO;
+        if !ascending && !descending {
+            return Err(ArangeError::InvalidStep);
+        }
+        let len = T::steps(start, end, step).ok_or(ArangeError::InvalidRange)?;
+        let mut buffer = AlignedBuffer::<T>::uninit(len).map_err(Error::from)?;
+        for (index, slot) in buffer.iter_mut().enumerate() {
+            slot.write(T::offset(start, step, index).ok_or(ArangeError::InvalidRange)?);
+        }
+        // SAFETY: every element was written above.
+        let buffer = unsafe { buffer.assume_init() };
+        let len = i64::try_from(len).map_err(|_| Error::SpanOverflow)?;
+        let initialized = fixed::Initialized::from_aligned(buffer, [len])?;
+        // SAFETY: the descriptor addresses only elements of the owned buffer,
+        // which lives until the tensor is dropped.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Creates an `n × n` CPU identity matrix.
+    pub fn eye<T>(n: i64) -> Result<Self, Error>
+    where
+        T: NumericElement + Send,
+    {
+        let shape = [n, n];
+        let len = element_count(&shape)?;
+        let mut buffer = AlignedBuffer::<T>::zeroed(len)?;
+        let stride = n as usize + 1;
+        for index in (0..len).step_by(stride) {
+            buffer[index] = T::ONE;
+        }
+        let initialized = fixed::Initialized::from_aligned(buffer, shape)?;
+        // SAFETY: as in `arange`.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Creates a 0-dimensional CPU tensor holding `value`.
+    ///
+    /// The tensor has `ndim == 0` with null shape and strides pointers, which
+    /// DLPack permits for scalars; it still holds exactly one element.
+    pub fn scalar<T>(value: T) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        let buffer = AlignedBuffer::from_exact_iter([value])?;
+        let mut initialized = fixed::Initialized::<M, 0>::from_aligned(buffer, [])?;
+        let tensor = initialized.tensor_mut();
+        tensor.shape = std::ptr::null_mut();
+        tensor.strides = std::ptr::null_mut();
+        // SAFETY: as in `arange`; a 0-d tensor reads no shape or strides.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArangeError;
+    use crate::{
+        DlpackFlags, Local,
+        allocation::DEFAULT_ALIGNMENT,
+        ffi::{DLDataType, DLManagedTensor, DLManagedTensorVersioned},
+        metadata::Error,
+    };
+
+    type Versioned = Local<DLManagedTensorVersioned>;
+
+    #[test]
+    fn filled_tensors_are_aligned_and_owned() {
+        let zeros = Versioned::zeros::<f64>(&[2, 3]).unwrap();
+        assert_eq!(zeros.shape().unwrap(), &[2, 3]);
+        assert_eq!(zeros.cpu_slice::<f64>().unwrap(), &[0.0; 6]);
+        assert_eq!(zeros.flags(), DlpackFlags::IS_COPIED);
+        let data = zeros.cpu_slice::<f64>().unwrap().as_ptr();
+        assert!(data.addr().is_multiple_of(DEFAULT_ALIGNMENT));
+
+        let ones = Local::<DLManagedTensor>::ones::<u8>(&[4]).unwrap();
+        assert_eq!(ones.cpu_slice::<u8>().unwrap(), &[1; 4]);
+
+        let full = Versioned::full(&[2, 2], -7_i16).unwrap();
+        assert_eq!(full.dtype(), DLDataType::I16);
+        assert_eq!(full.cpu_slice::<i16>().unwrap(), &[-7; 4]);
+
+        let empty = Versioned::zeros::<f32>(&[3, 0]).unwrap();
+        assert_eq!(empty.num_elements().unwrap(), 0);
+
+        assert!(matches!(
+            Versioned::zeros::<f32>(&[2, -1]),
+            Err(Error::NegativeShape { axis: 1, value: -1 })
+        ));
+    }
+
+    #[test]
+    fn arange_follows_the_step_direction() {
+        let up = Versioned::arange(0_i32, 10, 3).unwrap();
+        assert_eq!(up.cpu_slice::<i32>().unwrap(), &[0, 3, 6, 9]);
+
+        let down = Versioned::arange(5_i64, -1, -2).unwrap();
+        assert_eq!(down.cpu_slice::<i64>().unwrap(), &[5, 3, 1]);
+
+        let floats = Versioned::arange(0.0_f32, 1.0, 0.25).unwrap();
+        assert_eq!(floats.cpu_slice::<f32>().unwrap(), &[0.0, 0.25, 0.5, 0.75]);
+
+        // Stops before the value would overflow.
+        let bytes = Versioned::arange(0_u8, 255, 100).unwrap();
+        assert_eq!(bytes.cpu_slice::<u8>().unwrap(), &[0, 100, 200]);
+
+        let empty = Versioned::arange(3_u32, 3, 1).unwrap();
+        assert_eq!(empty.shape().unwrap(), &[0]);
+
+        assert!(matches!(
+            Versioned::arange(0_i32, 4, 0),
+            Err(ArangeError::InvalidStep)
+        ));
+        assert!(matches!(
+            Versioned::arange(0.0_f64, 4.0, f64::NAN),
+            Err(ArangeError::InvalidStep)
+        ));
+    }
+
+    #[test]
+    fn arange_rejects_unbounded_ranges_before_allocating() {
+        assert!(matches!(
+            Versioned::arange(0.0_f64, f64::INFINITY, 1.0),
+            Err(ArangeError::InvalidRange)
+        ));
+        assert!(matches!(
+            Versioned::arange(f64::NAN, 1.0, 1.0),
+            Err(ArangeError::InvalidRange)
+        ));
+        assert!(matches!(
+            Versioned::arange(0.0_f32, f32::MAX, f32::MIN_POSITIVE),
+            Err(ArangeError::InvalidRange)
+        ));
+        assert!(matches!(
+            Versioned::arange(0_i64, i64::MAX, 1),
+            Err(ArangeError::Metadata {
+                source: Error::Allocation { .. }
+            })
+        ));
+
+        // 0.1 * 3 rounds above 0.3, so the count of 3 is trimmed to exclude it.
+        let floats = Versioned::arange(0.0_f64, 0.3, 0.1).unwrap();
+        assert_eq!(floats.cpu_slice::<f64>().unwrap(), &[0.0, 0.1, 0.2]);
+        let down = Versioned::arange(-1_i8, i8::MIN, i8::MIN).unwrap();
+        assert_eq!(down.cpu_slice::<i8>().unwrap(), &[-1]);
+    }
+
+    #[test]
+    fn integer_ranges_may_have_more_elements_than_the_type_can_count() {
+        let signed = Versioned::arange(i8::MIN, i8::MAX, 1).unwrap();
+        let expected = (i8::MIN..i8::MAX).collect::<Vec<_>>();
+        assert_eq!(signed.cpu_slice::<i8>().unwrap(), expected);
+        let around_zero = Versioned::arange(-100_i8, 100, 1).unwrap();
+        assert_eq!(around_zero.num_elements().unwrap(), 200);
+
+        let unsigned = Versioned::arange(0_u8, u8::MAX, 1).unwrap();
+        let expected = (0..u8::MAX).collect::<Vec<_>>();
+        assert_eq!(unsigned.cpu_slice::<u8>().unwrap(), expected);
+
+        // `step * index` overflows `i8` before `start` brings it back in range.
+        let down = Versioned::arange(127_i8, -128, -2).unwrap();
+        let expected = (-127..=127).rev().step_by(2).collect::<Vec<i8>>();
+        assert_eq!(down.cpu_slice::<i8>().unwrap(), expected);
+        let down = Versioned::arange(i64::MAX, i64::MIN, i64::MIN).unwrap();
+        assert_eq!(down.cpu_slice::<i64>().unwrap(), &[i64::MAX, -1]);
+    }
+
+    #[test]
+    fn eye_sets_the_diagonal() {
+        let eye = Versioned::eye::<f32>(3).unwrap();
+        assert_eq!(eye.shape().unwrap(), &[3, 3]);
+        assert_eq!(
+            eye.cpu_slice::<f32>().unwrap(),
+            &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0]
+        );
+        assert_eq!(Versioned::eye::<i8>(0).unwrap().num_elements().unwrap(), 0);
+    }
+
+    #[test]
+    fn scalars_have_null_shape_and_one_element() {
+        let scalar = Versioned::scalar(42_u64).unwrap();
+        assert_eq!(scalar.tensor().ndim, 0);
+        assert!(scalar.tensor().shape.is_null());
+        assert!(scalar.tensor().strides.is_null());
+        assert_eq!(scalar.shape().unwrap(), &[] as &[i64]);
+        assert_eq!(scalar.num_elements().unwrap(), 1);
+        assert_eq!(scalar.cpu_slice::<u64>().unwrap(), &[42]);
+        assert!(scalar.info().unwrap().is_compact);
+    }
+}
@@ -0,0 +1,312 @@
+//! Producers that memory-map a region of a file.
+//!
+//! The mapping itself becomes the manager context, so the tensor keeps the
+//! file mapped until it is dropped. Shared mappings are exported read-only;
+//! private copy-on-write mappings are writable and owned by the tensor.
+
+use super::{
+    dynamic,
+    vec::{compact_strides_error, element_span},
+};
+use crate::{
+    DlpackFlags, Local, ManagedTensorBase,
+    ffi::{DLDataType, DLDevice, DLManagedTensorVersioned},
+    metadata::{self, Copied, Dynamic},
+    tensor,
+};
+use memmap2::{Mmap, MmapMut, MmapOptions};
+use snafu::{ResultExt, Snafu};
+use std::fs::File;
+
+/// Alignment the mapped base pointer is rounded down to; the remainder of the
+/// file offset is carried in `byte_offset`.
+const BASE_ALIGNMENT: u64 = 256;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(transparent)]
+    Metadata { source: metadata::Error },
+
+    #[snafu(display("failed to map file region: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display(
+        "region of {len} bytes at offset {offset} exceeds the file length {file_len}"
+    ))]
+    OutOfBounds {
+        offset: u64,
+        len: u64,
+        file_len: u64,
+    },
+}
+
+/// The layout of a tensor stored at some offset in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRegion {
+    pub dtype: DLDataType,
+    pub shape: Vec<i64>,
+    /// Non-negative strides in elements, or `None` for compact row-major.
+    pub strides: Option<Vec<i64>>,
+    /// Byte offset of the first element within the file.
+    pub offset: u64,
+}
+
+impl FileRegion {
+    /// Describes a compact row-major tensor at the start of the file.
+    pub fn new(dtype: DLDataType, shape: impl Into<Vec<i64>>) -> Self {
+        Self {
+            dtype,
+            shape: shape.into(),
+            strides: None,
+            offset: 0,
+        }
+    }
+
+    /// Sets explicit strides in elements.
+    pub fn with_strides(mut self, strides: impl Into<Vec<i64>>) -> Self {
+        self.strides = Some(strides.into());
+        self
+    }
+
+    /// Sets the byte offset of the first element.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Resolves strides and the number of bytes the region reaches from
+    /// its first element.
+    pub(crate) fn layout(&self) -> Result<(Vec<i64>, u64), metadata::Error> {
+        let strides = match &self.strides {
+            Some(strides) => strides.clone(),
+            None => tensor::compact_strides(&self.shape).map_err(compact_strides_error)?,
+        };
+        let bits = u64::from(self.dtype.bits) * u64::from(self.dtype.lanes);
+        let span = element_span(&self.shape, &strides)? as u64;
+        let bytes = span
+            .checked_mul(bits)
+            .ok_or(metadata::Error::SpanOverflow)?
+            .div_ceil(8);
+        Ok((strides, bytes))
+    }
+}
+
+/// A validated mapping request: where to map and how much.
+struct Plan {
+    strides: Vec<i64>,
+    /// File offset of the mapping, rounded down to [`BASE_ALIGNMENT`].
+    map_offset: u64,
+    map_len: usize,
+    byte_offset: u64,
+}
+
+fn plan(file: &File, region: &FileRegion) -> Result<Plan, Error> {
+    let (strides, len) = region.layout()?;
+    let file_len = file.metadata().context(IoSnafu)?.len();
+    let out_of_bounds = || Error::OutOfBounds {
+        offset: region.offset,
+        len,
+        file_len,
+    };
+    let end = region.offset.checked_add(len).ok_or_else(out_of_bounds)?;
+    if end > file_len {
+        return Err(out_of_bounds());
+    }
+
+    let byte_offset = region.offset % BASE_ALIGNMENT;
+    let map_len = usize::try_from(len + byte_offset).map_err(|_| out_of_bounds())?;
+    Ok(Plan {
+        strides,
+        map_offset: region.offset - byte_offset,
+        map_len,
+        byte_offset,
+    })
+}
+
+impl Plan {
+    fn options(&self) -> MmapOptions {
+        let mut options = MmapOptions::new();
+        options.offset(self.map_offset).len(self.map_len);
+        options
+    }
+
+    fn export<M, C>(
+        self,
+        region: &FileRegion,
+        data: *const u8,
+        ctx: C,
+        flags: DlpackFlags,
+    ) -> Result<dynamic::Initialized<M>, Error>
+    where
+        M: ManagedTensorBase,
+        C: crate::OpaqueContext,
+    {
+        let (data, byte_offset) = if region.shape.contains(&0) {
+            (std::ptr::null_mut(), 0)
+        } else {
+            (data.cast_mut().cast(), self.byte_offset)
+        };
+        let prepared =
+            Dynamic::new(Copied(&region.shape[..]), Copied(self.strides)).prepare::<M>()?;
+        let mut initialized = prepared.initialize(ctx).map_err(metadata::Error::from)?;
+        initialized.set_data(data);
+        initialized.set_byte_offset(byte_offset);
+        initialized.set_dtype(region.dtype);
+        initialized.set_device(DLDevice::CPU);
+        initialized.set_flags_unchecked(flags);
+        Ok(initialized)
+    }
+}
+
+impl dynamic::Initialized<DLManagedTensorVersioned> {
+    /// Maps `region` of `file` as a shared, read-only CPU tensor.
+    ///
+    /// The data pointer is 256-byte aligned, with the rest of the file offset
+    /// carried in `byte_offset`. The tensor is marked
+    /// [`DlpackFlags::READ_ONLY`]; only the versioned ABI can express that.
+    ///
+    /// # Safety
+    ///
+    /// The mapped bytes must not be modified or truncated, by this or any
+    /// other process, while the tensor is alive.
+    pub unsafe fn from_mmap(file: &File, region: &FileRegion) -> Result<Self, Error> {
+        let plan = plan(file, region)?;
+        let mmap: Mmap = unsafe { plan.options().map(file) }.context(IoSnafu)?;
+        let data = mmap.as_ptr();
+        plan.export(region, data, Box::new(mmap), DlpackFlags::READ_ONLY)
+    }
+}
+
+impl<M: ManagedTensorBase> dynamic::Initialized<M> {
+    /// Maps `region` of `file` privately, copy-on-write, as a writable CPU
+    /// tensor.
+    ///
+    /// Writes go to private pages and never reach the file, so the tensor
+    /// owns its view and starts with [`DlpackFlags::IS_COPIED`].
+    ///
+    /// # Safety
+    ///
+    /// Pages not yet written still read through to the file, so the mapped
+    /// bytes must not be modified or truncated while the tensor is alive.
+    pub unsafe fn from_mmap_copy(file: &File, region: &FileRegion) -> Result<Self, Error> {
+        let plan = plan(file, region)?;
+        let mut mmap: MmapMut = unsafe { plan.options().map_copy(file) }.context(IoSnafu)?;
+        let data = mmap.as_mut_ptr();
+        plan.export(region, data, Box::new(mmap), DlpackFlags::IS_COPIED)
+    }
+}
+
+impl Local<DLManagedTensorVersioned> {
+    /// Maps `region` of `file` as a shared, read-only CPU tensor.
+    ///
+    /// Shorthand for [`dynamic::Initialized::from_mmap`] followed by
+    /// `finish`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`dynamic::Initialized::from_mmap`].
+    pub unsafe fn from_mmap(file: &File, region: &FileRegion) -> Result<Self, Error> {
+        let initialized = unsafe { dynamic::Initialized::from_mmap(file, region) }?;
+        // SAFETY: the region was checked against the file length and the
+        // mapping lives as long as the tensor.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+impl<M: ManagedTensorBase> Local<M> {
+    /// Maps `region` of `file` privately, copy-on-write, as a writable CPU
+    /// tensor.
+    ///
+    /// Shorthand for [`dynamic::Initialized::from_mmap_copy`] followed by
+    /// `finish`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`dynamic::Initialized::from_mmap_copy`].
+    pub unsafe fn from_mmap_copy(file: &File, region: &FileRegion) -> Result<Self, Error> {
+        let initialized = unsafe { dynamic::Initialized::from_mmap_copy(file, region) }?;
+        // SAFETY: as in `from_mmap`.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Seek, SeekFrom, Write};
+
+    fn file_with(values: &[f32], padding: usize) -> File {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&vec![0xAB; padding]).unwrap();
+        for value in values {
+            file.write_all(&value.to_ne_bytes()).unwrap();
+        }
+        file.seek(SeekFrom::Start(0)).unwrap();
+        file
+    }
+
+    #[test]
+    fn shared_mapping_is_read_only_with_aligned_base() {
+        let values = [1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let file = file_with(&values, 300);
+        let region = FileRegion::new(DLDataType::F32, [2, 3]).with_offset(300);
+
+        let tensor = unsafe { Local::from_mmap(&file, &region) }.unwrap();
+        assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(tensor.byte_offset(), 300 % BASE_ALIGNMENT);
+        assert!(tensor.tensor().data.addr().is_multiple_of(256));
+        assert_eq!(tensor.shape().unwrap(), &[2, 3]);
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap(), &values);
+    }
+
+    #[test]
+    fn strided_region_is_checked_against_the_file_length() {
+        let file = file_with(&[0.0; 6], 0);
+
+        let region = FileRegion::new(DLDataType::F32, [3]).with_strides([2]);
+        let tensor = unsafe { Local::from_mmap(&file, &region) }.unwrap();
+        assert_eq!(tensor.strides().unwrap().unwrap(), &[2]);
+
+        let region = FileRegion::new(DLDataType::F32, [4]).with_strides([2]);
+        let error = unsafe { Local::from_mmap(&file, &region) }.err().unwrap();
+        assert!(matches!(
+            error,
+            Error::OutOfBounds {
+                len: 28,
+                file_len: 24,
+                ..
+            }
+        ));
+
+        let region = FileRegion::new(DLDataType::F32, [6]).with_offset(4);
+        assert!(unsafe { Local::from_mmap(&file, &region) }.is_err());
+    }
+
+    #[test]
+    fn private_mapping_is_writable_and_leaves_the_file_alone() {
+        let values = [1.0_f32, 2.0, 3.0, 4.0];
+        let mut file = file_with(&values, 0);
+        let region = FileRegion::new(DLDataType::F32, [4]);
+
+        let mut tensor =
+            unsafe { Local::<DLManagedTensorVersioned>::from_mmap_copy(&file, &region) }.unwrap();
+        assert_eq!(tensor.flags(), DlpackFlags::IS_COPIED);
+        tensor.cpu_slice_mut::<f32>().unwrap()[0] = 10.0;
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap(), &[10.0, 2.0, 3.0, 4.0]);
+        drop(tensor);
+
+        let mut first = [0u8; 4];
+        file.seek(SeekFrom::Start(0)).unwrap();
+        std::io::Read::read_exact(&mut file, &mut first).unwrap();
+        assert_eq!(f32::from_ne_bytes(first), 1.0);
+    }
+
+    #[test]
+    fn empty_region_maps_nothing() {
+        let file = file_with(&[], 0);
+        let region = FileRegion::new(DLDataType::F32, [0, 4]);
+        let tensor = unsafe { Local::from_mmap(&file, &region) }.unwrap();
+        assert!(tensor.tensor().data.is_null());
+        assert_eq!(tensor.num_elements().unwrap(), 0);
+    }
+}
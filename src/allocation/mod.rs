@@ -3,8 +3,15 @@
 use snafu::Snafu;
 use std::{alloc::Layout, ptr::NonNull};
 
+mod aligned;
+mod constructors;
 pub mod dynamic;
 pub mod fixed;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod scoped;
+mod shared;
+mod vec;
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -13,8 +20,14 @@ pub enum Error {
 
     #[snafu(display("managed tensor allocation layout overflows usize"))]
     LayoutOverflow,
+
+    #[snafu(display("alignment {align} is not a power of two of at least {min}"))]
+    InvalidAlignment { align: usize, min: usize },
 }
 
+pub use aligned::{AlignedBuffer, DEFAULT_ALIGNMENT};
+pub use constructors::ArangeError;
+
 /// An initialized managed tensor paired with allocation-specific metadata.
 pub struct Initialized<M: crate::ManagedTensorBase, Storage> {
     pub(super) managed: crate::Local<M>,
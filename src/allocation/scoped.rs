@@ -0,0 +1,296 @@
+//! Scoped exports of borrowed CPU memory.
+//!
+//! [`Local`] carries no lifetime, so borrowed data can only be exported for
+//! the duration of a closure. When the closure returns, or unwinds, the
+//! export checks that the consumer has released the tensor, meaning its
+//! deleter has run, before the borrow ends. A consumer that kept the tensor
+//! would go on to read memory the caller may free, so that case aborts the
+//! process, after an optional wait.
+//!
+//! This suits synchronous calls into kernels that do not retain their
+//! inputs.
+
+use super::{dynamic, vec::Elements};
+use crate::{
+    DlpackElement, DlpackFlags, Local, ManagedTensorBase, ffi::DLManagedTensorVersioned,
+    metadata::Error,
+};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Condvar, Mutex, PoisonError},
+    time::Duration,
+};
+
+/// What a scoped export does if the consumer still holds the tensor when the
+/// closure returns.
+///
+/// Either way, an unreleased tensor aborts the process with
+/// [`std::process::abort`], which prints nothing and does not unwind:
+/// unwinding would end the borrow while the consumer can still reach the
+/// memory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReleasePolicy {
+    /// Abort the process immediately.
+    #[default]
+    Abort,
+    /// Block for up to the given duration for the deleter to run, then abort.
+    Wait(Duration),
+}
+
+/// Layout and release policy for a scoped export of borrowed memory.
+#[derive(Debug, Clone, Copy)]
+pub struct ScopedExport<'s> {
+    shape: &'s [i64],
+    strides: Option<&'s [i64]>,
+    policy: ReleasePolicy,
+}
+
+impl<'s> ScopedExport<'s> {
+    /// Describes a compact row-major tensor of `shape`.
+    pub fn new(shape: &'s [i64]) -> Self {
+        Self {
+            shape,
+            strides: None,
+            policy: ReleasePolicy::Abort,
+        }
+    }
+
+    /// Sets explicit non-negative strides in elements.
+    pub fn with_strides(mut self, strides: &'s [i64]) -> Self {
+        self.strides = Some(strides);
+        self
+    }
+
+    /// Sets what happens if the tensor is not released in time.
+    pub fn with_policy(mut self, policy: ReleasePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Exports `data` read-only for the duration of `f`.
+    ///
+    /// The tensor is marked [`DlpackFlags::READ_ONLY`], which only the
+    /// versioned ABI can express. After `f` returns, the tensor must have been
+    /// dropped or its deleter called; see [`ReleasePolicy`].
+    pub fn run<T, F, R>(&self, data: &[T], f: F) -> Result<R, Error>
+    where
+        T: DlpackElement + Sync,
+        F: FnOnce(Local<DLManagedTensorVersioned>) -> R,
+    {
+        self.export::<_, T, _, _, false>(data.as_ptr(), data.len(), f)
+    }
+
+    /// Exports `data` writable for the duration of `f`.
+    ///
+    /// The caller keeps ownership, so the tensor is not marked
+    /// [`DlpackFlags::IS_COPIED`]. Release is checked as in [`Self::run`].
+    pub fn run_mut<M, T, F, R>(&self, data: &mut [T], f: F) -> Result<R, Error>
+    where
+        M: ManagedTensorBase,
+        T: DlpackElement + Send,
+        F: FnOnce(Local<M>) -> R,
+    {
+        self.export::<_, T, _, _, true>(data.as_mut_ptr(), data.len(), f)
+    }
+
+    fn export<M, T, F, R, const WRITABLE: bool>(
+        &self,
+        ptr: *const T,
+        len: usize,
+        f: F,
+    ) -> Result<R, Error>
+    where
+        M: ManagedTensorBase,
+        T: DlpackElement,
+        F: FnOnce(Local<M>) -> R,
+    {
+        let release = Arc::new(Release::default());
+        let elements = Scoped::<T, WRITABLE> {
+            ptr,
+            len,
+            guard: ReleaseGuard(Arc::clone(&release)),
+            _element: PhantomData,
+        };
+        let initialized = dynamic::Initialized::from_elements(elements, self.shape, self.strides)?;
+
+        let _scope = Scope {
+            release: &release,
+            policy: self.policy,
+        };
+        // SAFETY: the descriptor addresses only elements of the borrowed
+        // slice, and `_scope` keeps the borrow alive until the tensor is
+        // released, on return and on unwind alike.
+        let tensor = unsafe { initialized.finish() };
+        Ok(f(tensor))
+    }
+}
+
+/// Exports `data` read-only as a compact row-major tensor of `shape` for
+/// the duration of `f`, aborting if the consumer keeps it afterwards.
+///
+/// Shorthand for [`ScopedExport::run`].
+pub fn with_borrowed_dlpack<T, F, R>(data: &[T], shape: &[i64], f: F) -> Result<R, Error>
+where
+    T: DlpackElement + Sync,
+    F: FnOnce(Local<DLManagedTensorVersioned>) -> R,
+{
+    ScopedExport::new(shape).run(data, f)
+}
+
+/// Exports `data` writable as a compact row-major tensor of `shape` for the
+/// duration of `f`, aborting if the consumer keeps it afterwards.
+///
+/// Shorthand for [`ScopedExport::run_mut`].
+pub fn with_borrowed_dlpack_mut<M, T, F, R>(data: &mut [T], shape: &[i64], f: F) -> Result<R, Error>
+where
+    M: ManagedTensorBase,
+    T: DlpackElement + Send,
+    F: FnOnce(Local<M>) -> R,
+{
+    ScopedExport::new(shape).run_mut(data, f)
+}
+
+/// Set once the tensor's context is dropped, on whichever thread runs the
+/// deleter.
+#[derive(Default)]
+struct Release {
+    released: Mutex<bool>,
+    signal: Condvar,
+}
+
+/// The manager context of a scoped export.
+struct ReleaseGuard(Arc<Release>);
+
+impl Drop for ReleaseGuard {
+    fn drop(&mut self) {
+        let release = &self.0;
+        *release
+            .released
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = true;
+        release.signal.notify_all();
+    }
+}
+
+/// Waits for the release when the borrow is about to end.
+struct Scope<'a> {
+    release: &'a Release,
+    policy: ReleasePolicy,
+}
+
+impl Drop for Scope<'_> {
+    fn drop(&mut self) {
+        let released = self
+            .release
+            .released
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let released = match self.policy {
+            ReleasePolicy::Abort => released,
+            ReleasePolicy::Wait(timeout) => {
+                self.release
+                    .signal
+                    .wait_timeout_while(released, timeout, |released| !*released)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+        };
+        if !*released {
+            // The consumer still holds the tensor; see `ReleasePolicy`.
+            std::process::abort();
+        }
+    }
+}
+
+/// A borrowed slice exported for one scope.
+struct Scoped<T, const WRITABLE: bool> {
+    ptr: *const T,
+    len: usize,
+    guard: ReleaseGuard,
+    _element: PhantomData<T>,
+}
+
+impl<T, const WRITABLE: bool> Elements<T> for Scoped<T, WRITABLE> {
+    type Context = Box<ReleaseGuard>;
+    const FLAGS: DlpackFlags = if WRITABLE {
+        DlpackFlags::empty()
+    } else {
+        DlpackFlags::READ_ONLY
+    };
+
+    fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self.guard)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::DLManagedTensor;
+    use std::time::Instant;
+
+    #[test]
+    fn borrowed_slice_is_read_only_for_the_scope() {
+        let data = [1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        let sum = with_borrowed_dlpack(&data[1..], &[2, 3], |tensor| {
+            assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+            assert_eq!(tensor.shape().unwrap(), &[2, 3]);
+            tensor.cpu_slice::<f32>().unwrap().iter().sum::<f32>()
+        })
+        .unwrap();
+        assert_eq!(sum, 27.0);
+
+        let strided = ScopedExport::new(&[3])
+            .with_strides(&[2])
+            .run(&data, |tensor| tensor.strides().unwrap().unwrap().to_vec())
+            .unwrap();
+        assert_eq!(strided, [2]);
+
+        assert!(matches!(
+            with_borrowed_dlpack(&data, &[2, 4], drop),
+            Err(Error::BufferTooShort {
+                required: 8,
+                len: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn mutable_borrow_writes_through_without_is_copied() {
+        let mut data = [0_i32; 4];
+        with_borrowed_dlpack_mut::<DLManagedTensor, _, _, _>(&mut data, &[4], |mut tensor| {
+            unsafe { tensor.cpu_slice_mut_unchecked::<i32>() }
+                .unwrap()
+                .copy_from_slice(&[1, 2, 3, 4]);
+        })
+        .unwrap();
+        assert_eq!(data, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn wait_policy_blocks_until_a_late_release() {
+        let data = [0_u8; 16];
+        let start = Instant::now();
+        let handle = ScopedExport::new(&[16])
+            .with_policy(ReleasePolicy::Wait(Duration::from_secs(30)))
+            .run(&data, |tensor| {
+                let raw = tensor.into_raw() as usize;
+                std::thread::spawn(move || {
+                    std::thread::sleep(Duration::from_millis(50));
+                    let raw = raw as *mut DLManagedTensorVersioned;
+                    unsafe { ((*raw).deleter.unwrap())(raw) };
+                })
+            })
+            .unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+//! Zero-copy, read-only producers for shared `Arc<[T]>`, `Arc<Vec<T>>` and
+//! `bytes::Bytes` buffers.
+//!
+//! Other owners may still read the memory, so these tensors always carry
+//! [`DlpackFlags::READ_ONLY`] and never `IS_COPIED`. Only the versioned ABI
+//! can express that, so the producers are limited to
+//! [`DLManagedTensorVersioned`].
+
+use super::{dynamic, vec::Elements};
+use crate::{DlpackElement, DlpackFlags, Local, ffi::DLManagedTensorVersioned, metadata::Error};
+use std::sync::Arc;
+
+impl<T: Send + Sync + 'static> Elements<T> for Arc<[T]> {
+    // `Arc<[T]>` is a fat pointer, so it is boxed to fit the context slot.
+    type Context = Box<Self>;
+    const FLAGS: DlpackFlags = DlpackFlags::READ_ONLY;
+
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> Elements<T> for Arc<Vec<T>> {
+    type Context = Self;
+    const FLAGS: DlpackFlags = DlpackFlags::READ_ONLY;
+
+    fn as_ptr(&self) -> *const T {
+        self.as_slice().as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn into_context(self) -> Self::Context {
+        self
+    }
+}
+
+/// A `Bytes` buffer reinterpreted as elements of `T`.
+#[cfg(feature = "bytes")]
+struct TypedBytes<T> {
+    bytes: bytes::Bytes,
+    _element: std::marker::PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "bytes")]
+impl<T: DlpackElement> TypedBytes<T> {
+    fn new(bytes: bytes::Bytes) -> Result<Self, Error> {
+        let element_size = size_of::<T>();
+        if !bytes.len().is_multiple_of(element_size) {
+            return Err(Error::BufferLengthNotMultiple {
+                len: bytes.len(),
+                element_size,
+            });
+        }
+        // An empty buffer is never dereferenced, whatever its address.
+        let align = align_of::<T>();
+        if !bytes.is_empty() && !bytes.as_ptr().cast::<T>().is_aligned() {
+            return Err(Error::MisalignedBuffer {
+                addr: bytes.as_ptr() as usize,
+                align,
+            });
+        }
+        Ok(Self {
+            bytes,
+            _element: std::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl<T> Elements<T> for TypedBytes<T> {
+    type Context = Box<bytes::Bytes>;
+    const FLAGS: DlpackFlags = DlpackFlags::READ_ONLY;
+
+    fn as_ptr(&self) -> *const T {
+        self.bytes.as_ptr().cast()
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len() / size_of::<T>()
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self.bytes)
+    }
+}
+
+impl dynamic::Initialized<DLManagedTensorVersioned> {
+    /// Exports a shared slice as a read-only, compact row-major CPU tensor
+    /// without copying.
+    ///
+    /// The tensor holds one strong reference until it is dropped. The slice
+    /// may hold more elements than `shape` addresses.
+    pub fn from_arc_slice<T>(data: Arc<[T]>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_arc_slice`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_arc_slice_with_strides<T>(
+        data: Arc<[T]>,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    /// Exports a shared vector as a read-only, compact row-major CPU tensor
+    /// without copying. See [`Self::from_arc_slice`].
+    pub fn from_arc_vec<T>(data: Arc<Vec<T>>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_arc_vec`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_arc_vec_with_strides<T>(
+        data: Arc<Vec<T>>,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    /// Exports a `Bytes` buffer as a read-only, compact row-major CPU tensor
+    /// of `T` without copying.
+    ///
+    /// The buffer length must be a multiple of `size_of::<T>()` and a
+    /// non-empty buffer must be aligned for `T`.
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes<T>(data: bytes::Bytes, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement,
+    {
+        Self::from_elements(TypedBytes::<T>::new(data)?, shape, None)
+    }
+
+    /// Like [`Self::from_bytes`], with explicit non-negative strides in
+    /// elements.
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes_with_strides<T>(
+        data: bytes::Bytes,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement,
+    {
+        Self::from_elements(TypedBytes::<T>::new(data)?, shape, Some(strides))
+    }
+}
+
+impl Local<DLManagedTensorVersioned> {
+    /// Exports a shared slice as a read-only, compact row-major CPU tensor
+    /// without copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_arc_slice`] followed
+    /// by `finish`.
+    pub fn from_arc_slice<T>(data: Arc<[T]>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        let initialized = dynamic::Initialized::from_arc_slice(data, shape)?;
+        // SAFETY: the descriptor addresses only elements of the shared buffer,
+        // which the context keeps alive, and is marked read-only.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Exports a shared vector as a read-only, compact row-major CPU tensor
+    /// without copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_arc_vec`] followed by
+    /// `finish`.
+    pub fn from_arc_vec<T>(data: Arc<Vec<T>>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send + Sync,
+    {
+        let initialized = dynamic::Initialized::from_arc_vec(data, shape)?;
+        // SAFETY: as in `from_arc_slice`.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Exports a `Bytes` buffer as a read-only, compact row-major CPU tensor
+    /// of `T` without copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_bytes`] followed by
+    /// `finish`.
+    #[cfg(feature = "bytes")]
+    pub fn from_bytes<T>(data: bytes::Bytes, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement,
+    {
+        let initialized = dynamic::Initialized::from_bytes::<T>(data, shape)?;
+        // SAFETY: as in `from_arc_slice`; `TypedBytes` checked alignment and
+        // length.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DlpackFlags, Local, allocation::dynamic, ffi::DLDataType, ffi::DLManagedTensorVersioned,
+        metadata::Error, tensor,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn arc_slice_exports_read_only_and_keeps_a_reference() {
+        let data: Arc<[f32]> = Arc::from([1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let mut tensor = Local::from_arc_slice(Arc::clone(&data), &[2, 3]).unwrap();
+        assert_eq!(Arc::strong_count(&data), 2);
+        assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(tensor.dtype(), DLDataType::F32);
+        assert_eq!(tensor.strides().unwrap().unwrap(), &[3, 1]);
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap().as_ptr(), data.as_ptr());
+        assert!(matches!(
+            tensor.cpu_slice_mut::<f32>(),
+            Err(tensor::Error::NotCopied)
+        ));
+        assert!(matches!(
+            unsafe { tensor.cpu_slice_mut_unchecked::<f32>() },
+            Err(tensor::Error::ReadOnly)
+        ));
+
+        drop(tensor);
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn arc_vec_uses_the_arc_as_context() {
+        let data = Arc::new(vec![1_i32, 2, 3, 4]);
+
+        let tensor = Local::from_arc_vec(Arc::clone(&data), &[2, 2]).unwrap();
+        assert_eq!(Arc::strong_count(&data), 2);
+        assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(tensor.cpu_slice::<i32>().unwrap(), &[1, 2, 3, 4]);
+
+        let foreign = tensor.into_foreign();
+        assert_eq!(Arc::strong_count(&data), 2);
+        drop(foreign);
+        assert_eq!(Arc::strong_count(&data), 1);
+    }
+
+    #[test]
+    fn shared_producers_check_the_span() {
+        let data: Arc<[u8]> = Arc::from([0_u8; 4]);
+        let error = dynamic::Initialized::<DLManagedTensorVersioned>::from_arc_slice_with_strides(
+            data,
+            &[2, 2],
+            &[3, 1],
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(
+            error,
+            Error::BufferTooShort {
+                required: 5,
+                len: 4
+            }
+        ));
+    }
+
+    /// Owns `f32`s so the resulting `Bytes` is aligned for them.
+    #[cfg(feature = "bytes")]
+    struct F32Owner(Box<[f32]>);
+
+    #[cfg(feature = "bytes")]
+    impl AsRef<[u8]> for F32Owner {
+        fn as_ref(&self) -> &[u8] {
+            // SAFETY: any initialized `f32` is valid as bytes.
+            unsafe { std::slice::from_raw_parts(self.0.as_ptr().cast(), size_of_val(&*self.0)) }
+        }
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes_are_reinterpreted_after_checks() {
+        let values = [1.5_f32, 2.5, 3.5, 4.5];
+        let bytes = bytes::Bytes::from_owner(F32Owner(Box::new(values)));
+
+        let tensor = Local::from_bytes::<f32>(bytes.clone(), &[2, 2]).unwrap();
+        assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(tensor.dtype(), DLDataType::F32);
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap(), &values);
+        assert_eq!(
+            tensor.cpu_slice::<f32>().unwrap().as_ptr().cast(),
+            bytes.as_ptr()
+        );
+
+        let error = Local::from_bytes::<f32>(bytes.slice(1..13), &[3])
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::MisalignedBuffer { align: 4, .. }));
+
+        let error = Local::from_bytes::<f32>(bytes.slice(..7), &[1])
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            Error::BufferLengthNotMultiple {
+                len: 7,
+                element_size: 4
+            }
+        ));
+    }
+}
@@ -0,0 +1,421 @@
+//! Zero-copy producers for owned `Vec<T>` and `Box<[T]>` element buffers.
+
+use super::{dynamic, fixed};
+use crate::{
+    DlpackElement, DlpackFlags, Local, ManagedTensorBase, OpaqueContext,
+    ffi::DLDevice,
+    metadata::{Copied, Dynamic, Error, Fixed},
+    tensor,
+};
+use std::ffi::c_void;
+
+/// A contiguous element buffer that can become a manager context.
+pub(super) trait Elements<T> {
+    type Context: OpaqueContext;
+
+    /// Flags describing who else may reach the buffer once exported.
+    const FLAGS: DlpackFlags;
+
+    fn as_ptr(&self) -> *const T;
+    fn len(&self) -> usize;
+    fn into_context(self) -> Self::Context;
+}
+
+impl<T: Send + 'static> Elements<T> for Vec<T> {
+    type Context = Box<Self>;
+    const FLAGS: DlpackFlags = DlpackFlags::IS_COPIED;
+
+    fn as_ptr(&self) -> *const T {
+        self.as_slice().as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self)
+    }
+}
+
+impl<T: Send + 'static> Elements<T> for Box<[T]> {
+    type Context = Box<Self>;
+    const FLAGS: DlpackFlags = DlpackFlags::IS_COPIED;
+
+    fn as_ptr(&self) -> *const T {
+        (**self).as_ptr()
+    }
+
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    fn into_context(self) -> Self::Context {
+        Box::new(self)
+    }
+}
+
+/// Validates `shape` and `strides` against a buffer of `len` elements.
+///
+/// Strides must be non-negative so that every addressed element lies at or
+/// after the buffer start; the furthest one must lie before its end.
+fn check_span(shape: &[i64], strides: &[i64], len: usize) -> Result<(), Error> {
+    let required = element_span(shape, strides)?;
+    if required > len {
+        return Err(Error::BufferTooShort { required, len });
+    }
+    Ok(())
+}
+
+/// Returns how many elements from the base pointer `shape` and non-negative
+/// `strides` reach, or 0 for an empty tensor.
+pub(super) fn element_span(shape: &[i64], strides: &[i64]) -> Result<usize, Error> {
+    if shape.len() != strides.len() {
+        return Err(Error::MismatchedLength {
+            shape_len: shape.len(),
+            strides_len: strides.len(),
+        });
+    }
+    for (axis, &value) in shape.iter().enumerate() {
+        if value < 0 {
+            return Err(Error::NegativeShape { axis, value });
+        }
+    }
+    for (axis, &value) in strides.iter().enumerate() {
+        if value < 0 {
+            return Err(Error::NegativeStride { axis, value });
+        }
+    }
+    if shape.contains(&0) {
+        return Ok(0);
+    }
+
+    let mut required = 1usize;
+    for (&dim, &stride) in shape.iter().zip(strides) {
+        let reach = usize::try_from(dim - 1)
+            .ok()
+            .zip(usize::try_from(stride).ok())
+            .and_then(|(steps, stride)| steps.checked_mul(stride))
+            .ok_or(Error::SpanOverflow)?;
+        required = required.checked_add(reach).ok_or(Error::SpanOverflow)?;
+    }
+    Ok(required)
+}
+
+/// Reports a compact stride computation failure in terms of the shape.
+pub(super) fn compact_strides_error(error: tensor::Error) -> Error {
+    match error {
+        tensor::Error::NegativeDimension { axis, value } => Error::NegativeShape { axis, value },
+        _ => Error::SpanOverflow,
+    }
+}
+
+fn data_ptr<T>(data: &impl Elements<T>, shape: &[i64]) -> *mut c_void {
+    if shape.contains(&0) {
+        std::ptr::null_mut()
+    } else {
+        data.as_ptr().cast_mut().cast()
+    }
+}
+
+impl<M: ManagedTensorBase> dynamic::Initialized<M> {
+    /// Exports a vector as a compact row-major CPU tensor without copying.
+    ///
+    /// The vector becomes the manager context and the tensor starts with
+    /// [`DlpackFlags::IS_COPIED`], since no other owner of the data remains.
+    /// The vector may hold more elements than `shape` addresses.
+    pub fn from_vec<T>(data: Vec<T>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_vec`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_vec_with_strides<T>(
+        data: Vec<T>,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    /// Exports a boxed slice as a compact row-major CPU tensor without
+    /// copying. See [`Self::from_vec`].
+    pub fn from_boxed_slice<T>(data: Box<[T]>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_boxed_slice`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_boxed_slice_with_strides<T>(
+        data: Box<[T]>,
+        shape: &[i64],
+        strides: &[i64],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    pub(super) fn from_elements<T, D>(
+        data: D,
+        shape: &[i64],
+        strides: Option<&[i64]>,
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement,
+        D: Elements<T>,
+    {
+        let strides = match strides {
+            Some(strides) => strides.to_vec(),
+            None => tensor::compact_strides(shape).map_err(compact_strides_error)?,
+        };
+        check_span(shape, &strides, data.len())?;
+
+        let data_ptr = data_ptr(&data, shape);
+        let prepared = Dynamic::new(Copied(shape), Copied(strides)).prepare::<M>()?;
+        let mut initialized = prepared.initialize(data.into_context())?;
+        initialized.set_data(data_ptr);
+        initialized.set_dtype(T::DTYPE);
+        initialized.set_device(DLDevice::CPU);
+
+        // SAFETY: `D::FLAGS` only claims `IS_COPIED` for buffers whose sole
+        // owner is the context.
+        initialized.set_flags_unchecked(D::FLAGS);
+        Ok(initialized)
+    }
+}
+
+impl<M: ManagedTensorBase, const N: usize> fixed::Initialized<M, N> {
+    /// Exports a vector as a compact row-major fixed-rank CPU tensor without
+    /// copying. See [`dynamic::Initialized::from_vec`].
+    pub fn from_vec<T>(data: Vec<T>, shape: [i64; N]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_vec`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_vec_with_strides<T>(
+        data: Vec<T>,
+        shape: [i64; N],
+        strides: [i64; N],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    /// Exports a boxed slice as a compact row-major fixed-rank CPU tensor
+    /// without copying. See [`dynamic::Initialized::from_vec`].
+    pub fn from_boxed_slice<T>(data: Box<[T]>, shape: [i64; N]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, None)
+    }
+
+    /// Like [`Self::from_boxed_slice`], with explicit non-negative strides in
+    /// elements.
+    pub fn from_boxed_slice_with_strides<T>(
+        data: Box<[T]>,
+        shape: [i64; N],
+        strides: [i64; N],
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        Self::from_elements(data, shape, Some(strides))
+    }
+
+    pub(super) fn from_elements<T, D>(
+        data: D,
+        shape: [i64; N],
+        strides: Option<[i64; N]>,
+    ) -> Result<Self, Error>
+    where
+        T: DlpackElement,
+        D: Elements<T>,
+    {
+        let strides = match strides {
+            Some(strides) => strides,
+            None => tensor::compact_strides_array(shape).map_err(compact_strides_error)?,
+        };
+        check_span(&shape, &strides, data.len())?;
+
+        let data_ptr = data_ptr(&data, &shape);
+        let prepared = Fixed::new(Copied(shape), Copied(strides)).prepare::<M>()?;
+        let mut initialized = prepared.initialize(data.into_context());
+        initialized.set_data(data_ptr);
+        initialized.set_dtype(T::DTYPE);
+        initialized.set_device(DLDevice::CPU);
+
+        // SAFETY: `D::FLAGS` only claims `IS_COPIED` for buffers whose sole
+        // owner is the context.
+        initialized.set_flags_unchecked(D::FLAGS);
+        Ok(initialized)
+    }
+}
+
+impl<M: ManagedTensorBase> Local<M> {
+    /// Exports a vector as a compact row-major CPU tensor without copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_vec`] followed by
+    /// `finish`.
+    pub fn from_vec<T>(data: Vec<T>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        let initialized = dynamic::Initialized::from_vec(data, shape)?;
+        // SAFETY: the descriptor addresses only elements of the owned buffer,
+        // which lives until the tensor is dropped.
+        Ok(unsafe { initialized.finish() })
+    }
+
+    /// Exports a boxed slice as a compact row-major CPU tensor without
+    /// copying.
+    ///
+    /// Safe shorthand for [`dynamic::Initialized::from_boxed_slice`] followed
+    /// by `finish`.
+    pub fn from_boxed_slice<T>(data: Box<[T]>, shape: &[i64]) -> Result<Self, Error>
+    where
+        T: DlpackElement + Send,
+    {
+        let initialized = dynamic::Initialized::from_boxed_slice(data, shape)?;
+        // SAFETY: as in `from_vec`.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DlpackFlags, Local,
+        allocation::{dynamic, fixed},
+        ffi::{DLDataType, DLDevice, DLManagedTensor, DLManagedTensorVersioned},
+        metadata::Error,
+    };
+
+    #[test]
+    fn vec_exports_as_copied_compact_cpu_tensor() {
+        let data = vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let data_ptr = data.as_ptr();
+
+        let mut tensor = Local::<DLManagedTensorVersioned>::from_vec(data, &[2, 3]).unwrap();
+
+        assert_eq!(tensor.shape().unwrap(), &[2, 3]);
+        assert_eq!(tensor.strides().unwrap().unwrap(), &[3, 1]);
+        assert_eq!(tensor.dtype(), DLDataType::F32);
+        assert_eq!(tensor.device(), DLDevice::CPU);
+        assert_eq!(tensor.flags(), DlpackFlags::IS_COPIED);
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap().as_ptr(), data_ptr);
+
+        tensor.cpu_slice_mut::<f32>().unwrap()[5] = 60.0;
+        assert_eq!(tensor.cpu_slice::<f32>().unwrap()[5], 60.0);
+    }
+
+    #[test]
+    fn boxed_slice_and_fixed_rank_shapes_are_accepted() {
+        let boxed: Box<[u16]> = vec![1, 2, 3, 4].into_boxed_slice();
+        let tensor = Local::<DLManagedTensor>::from_boxed_slice(boxed, &[4]).unwrap();
+        assert_eq!(tensor.cpu_slice::<u16>().unwrap(), &[1, 2, 3, 4]);
+
+        let initialized =
+            fixed::Initialized::<DLManagedTensorVersioned, 2>::from_vec(vec![0_i8; 6], [3, 2])
+                .unwrap();
+        let tensor = unsafe { initialized.finish() };
+        assert_eq!(tensor.shape().unwrap(), &[3, 2]);
+        assert_eq!(tensor.strides().unwrap().unwrap(), &[2, 1]);
+
+        let boxed: Box<[i8]> = vec![0; 6].into_boxed_slice();
+        let initialized = fixed::Initialized::<DLManagedTensor, 2>::from_boxed_slice_with_strides(
+            boxed,
+            [3, 2],
+            [1, 3],
+        )
+        .unwrap();
+        let tensor = unsafe { initialized.finish() };
+        assert!(!tensor.is_compact().unwrap());
+    }
+
+    #[test]
+    fn explicit_strides_may_address_a_prefix_of_a_longer_buffer() {
+        let initialized = dynamic::Initialized::<DLManagedTensorVersioned>::from_vec_with_strides(
+            (0..10_i32).collect(),
+            &[2, 2],
+            &[1, 2],
+        )
+        .unwrap();
+        let tensor = unsafe { initialized.finish() };
+
+        assert_eq!(tensor.strides().unwrap().unwrap(), &[1, 2]);
+        assert_eq!(tensor.num_elements().unwrap(), 4);
+    }
+
+    #[test]
+    fn rejects_shapes_the_buffer_cannot_back() {
+        assert!(matches!(
+            Local::<DLManagedTensor>::from_vec(vec![0_u8; 5], &[2, 3]),
+            Err(Error::BufferTooShort {
+                required: 6,
+                len: 5
+            })
+        ));
+        assert!(matches!(
+            dynamic::Initialized::<DLManagedTensor>::from_vec_with_strides(
+                vec![0_u8; 6],
+                &[2, 3],
+                &[4, 1],
+            ),
+            Err(Error::BufferTooShort {
+                required: 7,
+                len: 6
+            })
+        ));
+        assert!(matches!(
+            dynamic::Initialized::<DLManagedTensor>::from_vec_with_strides(
+                vec![0_u8; 6],
+                &[2, 3],
+                &[-3, 1],
+            ),
+            Err(Error::NegativeStride { axis: 0, value: -3 })
+        ));
+        assert!(matches!(
+            Local::<DLManagedTensor>::from_vec(vec![0_u8; 6], &[-2, 3]),
+            Err(Error::NegativeShape { axis: 0, value: -2 })
+        ));
+        assert!(matches!(
+            dynamic::Initialized::<DLManagedTensor>::from_vec_with_strides(
+                vec![0_u8; 6],
+                &[2, 3],
+                &[1],
+            ),
+            Err(Error::MismatchedLength { .. })
+        ));
+        assert!(matches!(
+            Local::<DLManagedTensor>::from_vec(vec![0_u8; 6], &[i64::MAX, 2]),
+            Err(Error::SpanOverflow)
+        ));
+    }
+
+    #[test]
+    fn empty_shapes_export_a_null_data_pointer() {
+        let tensor =
+            Local::<DLManagedTensorVersioned>::from_vec(Vec::<f64>::new(), &[0, 3]).unwrap();
+
+        assert!(tensor.tensor().data_ptr().is_null());
+        assert_eq!(tensor.cpu_slice::<f64>().unwrap(), &[] as &[f64]);
+    }
+}
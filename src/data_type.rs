@@ -44,6 +44,124 @@ impl_dlpack_element!(half::f16, DLDataTypeCode::FLOAT, 16);
 #[cfg(feature = "half")]
 impl_dlpack_element!(half::bf16, DLDataTypeCode::BFLOAT, 16);
 
+/// Numeric element types with the arithmetic needed by
+/// [`Local::ones`](crate::Local::ones), [`Local::arange`](crate::Local::arange)
+/// and [`Local::eye`](crate::Local::eye).
+pub trait NumericElement: DlpackElement + Copy + PartialOrd {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Returns `start + step * index`, or `None` if it is not representable.
+    fn offset(start: Self, step: Self, index: usize) -> Option<Self>;
+
+    /// Returns the number of values `start + step * i` before `end`, or
+    /// `None` if it is not finite or does not fit in `usize`.
+    ///
+    /// This is `ceil((end - start) / step)` clamped at zero; float impls drop
+    /// the last value when rounding puts it at or past `end`.
+    fn steps(start: Self, end: Self, step: Self) -> Option<usize>;
+}
+
+/// Drops the last of `steps` values when float rounding puts it at or past
+/// `end`.
+fn trim_rounded<T: NumericElement>(start: T, end: T, step: T, steps: usize) -> usize {
+    let past_end = |value: T| {
+        if step > T::ZERO {
+            value >= end
+        } else {
+            value <= end
+        }
+    };
+    let last = steps
+        .checked_sub(1)
+        .and_then(|index| T::offset(start, step, index));
+    if last.is_some_and(past_end) {
+        steps - 1
+    } else {
+        steps
+    }
+}
+
+/// [`NumericElement::steps`] for values widened to `f64`.
+fn float_steps(start: f64, end: f64, step: f64) -> Option<usize> {
+    let steps = ((end - start) / step).ceil();
+    if !steps.is_finite() || steps >= usize::MAX as f64 {
+        None
+    } else {
+        Some(steps.max(0.0) as usize)
+    }
+}
+
+macro_rules! impl_numeric_int {
+    ($($ty:ty),*) => {$(
+        impl NumericElement for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn offset(start: Self, step: Self, index: usize) -> Option<Self> {
+                let index = i128::try_from(index).ok()?;
+                let value = i128::from(step)
+                    .checked_mul(index)?
+                    .checked_add(i128::from(start))?;
+                Self::try_from(value).ok()
+            }
+
+            fn steps(start: Self, end: Self, step: Self) -> Option<usize> {
+                let span = i128::from(end) - i128::from(start);
+                let step = i128::from(step);
+                if span == 0 || (span > 0) != (step > 0) {
+                    return Some(0);
+                }
+                usize::try_from(span.unsigned_abs().div_ceil(step.unsigned_abs())).ok()
+            }
+        }
+    )*};
+}
+
+impl_numeric_int!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+macro_rules! impl_numeric_float {
+    ($($ty:ty),*) => {$(
+        impl NumericElement for $ty {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+
+            fn offset(start: Self, step: Self, index: usize) -> Option<Self> {
+                Some(start + step * index as Self)
+            }
+
+            fn steps(start: Self, end: Self, step: Self) -> Option<usize> {
+                let steps = float_steps(start.into(), end.into(), step.into())?;
+                Some(trim_rounded(start, end, step, steps))
+            }
+        }
+    )*};
+}
+
+impl_numeric_float!(f32, f64);
+
+#[cfg(feature = "half")]
+macro_rules! impl_numeric_half {
+    ($($ty:ty),*) => {$(
+        impl NumericElement for $ty {
+            const ZERO: Self = <$ty>::ZERO;
+            const ONE: Self = <$ty>::ONE;
+
+            fn offset(start: Self, step: Self, index: usize) -> Option<Self> {
+                Some(<$ty>::from_f64(start.to_f64() + step.to_f64() * index as f64))
+            }
+
+            fn steps(start: Self, end: Self, step: Self) -> Option<usize> {
+                let steps = float_steps(start.to_f64(), end.to_f64(), step.to_f64())?;
+                Some(trim_rounded(start, end, step, steps))
+            }
+        }
+    )*};
+}
+
+#[cfg(feature = "half")]
+impl_numeric_half!(half::f16, half::bf16);
+
 macro_rules! impl_data_type {
     ($name:ident, $code:expr, $bits:expr) => {
         impl DLDataType {
@@ -131,6 +249,101 @@ impl DLDataType {
     }
 }
 
+impl PartialEq for DLDataType {
+    fn eq(&self, other: &Self) -> bool {
+        self.matches(*other)
+    }
+}
+
+impl Eq for DLDataType {}
+
+impl std::hash::Hash for DLDataType {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(&self.code, state);
+        std::hash::Hash::hash(&self.bits, state);
+        std::hash::Hash::hash(&self.lanes, state);
+    }
+}
+
+/// Readable names for the bundled data type codes, paired with the storage
+/// width implied by the name. Codes without an implied width are followed by
+/// their bit count (`float32`); the others are spelled on their own
+/// (`bool`, `float8_e4m3fn`).
+const CODE_NAMES: [(DLDataTypeCode, &str, Option<u8>); 18] = [
+    (DLDataTypeCode::INT, "int", None),
+    (DLDataTypeCode::UINT, "uint", None),
+    (DLDataTypeCode::FLOAT, "float", None),
+    (DLDataTypeCode::OPAQUEHANDLE, "handle", None),
+    (DLDataTypeCode::BFLOAT, "bfloat", None),
+    (DLDataTypeCode::COMPLEX, "complex", None),
+    (DLDataTypeCode::BOOL, "bool", Some(8)),
+    (DLDataTypeCode::FLOAT8_E3M4, "float8_e3m4", Some(8)),
+    (DLDataTypeCode::FLOAT8_E4M3, "float8_e4m3", Some(8)),
+    (
+        DLDataTypeCode::FLOAT8_E4M3B11FNUZ,
+        "float8_e4m3b11fnuz",
+        Some(8),
+    ),
+    (DLDataTypeCode::FLOAT8_E4M3FN, "float8_e4m3fn", Some(8)),
+    (DLDataTypeCode::FLOAT8_E4M3FNUZ, "float8_e4m3fnuz", Some(8)),
+    (DLDataTypeCode::FLOAT8_E5M2, "float8_e5m2", Some(8)),
+    (DLDataTypeCode::FLOAT8_E5M2FNUZ, "float8_e5m2fnuz", Some(8)),
+    (DLDataTypeCode::FLOAT8_E8M0FNU, "float8_e8m0fnu", Some(8)),
+    (DLDataTypeCode::FLOAT6_E2M3FN, "float6_e2m3fn", Some(6)),
+    (DLDataTypeCode::FLOAT6_E3M2FN, "float6_e3m2fn", Some(6)),
+    (DLDataTypeCode::FLOAT4_E2M1FN, "float4_e2m1fn", Some(4)),
+];
+
+/// Formats as a NumPy-style name such as `float32`, `bool` or `uint8x4`
+/// (`x` followed by the lane count for vectorized types).
+///
+/// Codes not covered by the bundled headers, and fixed-width codes with an
+/// unexpected bit count, are spelled `code<code>_bits<bits>`.
+impl std::fmt::Display for DLDataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = CODE_NAMES.iter().find(|(code, _, _)| *code == self.code);
+        match name {
+            Some((_, name, None)) => write!(f, "{name}{}", self.bits)?,
+            Some((_, name, Some(bits))) if *bits == self.bits => f.write_str(name)?,
+            _ => write!(f, "code{}_bits{}", self.code.0, self.bits)?,
+        }
+        if self.lanes != 1 {
+            write!(f, "x{}", self.lanes)?;
+        }
+        Ok(())
+    }
+}
+
+impl DLDataType {
+    /// Parses a name produced by the [`Display`](std::fmt::Display) impl.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some((scalar, lanes)) = name.rsplit_once('x')
+            && let Ok(lanes) = lanes.parse::<u16>()
+            && let Some((code, bits)) = parse_scalar_name(scalar)
+        {
+            return Some(Self::new(code, bits, lanes));
+        }
+        let (code, bits) = parse_scalar_name(name)?;
+        Some(Self::scalar(code, bits))
+    }
+}
+
+fn parse_scalar_name(name: &str) -> Option<(DLDataTypeCode, u8)> {
+    for (code, prefix, fixed) in CODE_NAMES {
+        match fixed {
+            Some(bits) if name == prefix => return Some((code, bits)),
+            None => {
+                if let Some(Ok(bits)) = name.strip_prefix(prefix).map(str::parse::<u8>) {
+                    return Some((code, bits));
+                }
+            }
+            _ => {}
+        }
+    }
+    let (code, bits) = name.strip_prefix("code")?.split_once("_bits")?;
+    Some((DLDataTypeCode(code.parse().ok()?), bits.parse().ok()?))
+}
+
 impl DLDataTypeCode {
     /// Returns whether this data type code is defined by the bundled DLPack
     /// headers.
@@ -192,4 +405,49 @@ mod knownness_tests {
             assert!(DLDataType::BF16.is::<half::bf16>());
         }
     }
+
+    #[test]
+    fn display_uses_numpy_style_names() {
+        assert_eq!(DLDataType::F32.to_string(), "float32");
+        assert_eq!(DLDataType::BF16.to_string(), "bfloat16");
+        assert_eq!(DLDataType::C64.to_string(), "complex64");
+        assert_eq!(DLDataType::BOOL.to_string(), "bool");
+        assert_eq!(DLDataType::F8E4M3FN.to_string(), "float8_e4m3fn");
+        assert_eq!(DLDataType::F4E2M1FN.to_string(), "float4_e2m1fn");
+        assert_eq!(
+            DLDataType::new(DLDataTypeCode::UINT, 8, 4).to_string(),
+            "uint8x4"
+        );
+        assert_eq!(
+            DLDataType::new(DLDataTypeCode(42), 8, 1).to_string(),
+            "code42_bits8"
+        );
+        assert_eq!(
+            DLDataType::new(DLDataTypeCode::BOOL, 1, 1).to_string(),
+            "code6_bits1"
+        );
+    }
+
+    #[test]
+    fn from_name_round_trips_display() {
+        for dtype in [
+            DLDataType::BOOL,
+            DLDataType::I8,
+            DLDataType::U64,
+            DLDataType::F16,
+            DLDataType::BF16,
+            DLDataType::C128,
+            DLDataType::F8E4M3B11FNUZ,
+            DLDataType::F6E3M2FN,
+            DLDataType::new(DLDataTypeCode::COMPLEX, 64, 2),
+            DLDataType::new(DLDataTypeCode::OPAQUEHANDLE, 64, 1),
+            DLDataType::new(DLDataTypeCode(42), 8, 1),
+            DLDataType::new(DLDataTypeCode::BOOL, 1, 16),
+        ] {
+            assert_eq!(DLDataType::from_name(&dtype.to_string()), Some(dtype));
+        }
+        for name in ["", "float", "complex", "int8x", "f32", "bool8", "float3000"] {
+            assert_eq!(DLDataType::from_name(name), None, "{name}");
+        }
+    }
 }
@@ -1,4 +1,43 @@
 use crate::ffi::{DLDevice, DLDeviceType};
+use bitflags::bitflags;
+
+bitflags! {
+    /// Non-CPU device types whose memory host code may dereference directly.
+    ///
+    /// CPU accessors and consumers accept only [`DLDeviceType::CPU`] by
+    /// default. Pinned host allocations and CUDA managed memory are also
+    /// valid host pointers, but reading them may require the producer's
+    /// stream to be synchronized first, so each category is opt-in.
+    #[repr(transparent)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct HostAccess: u8 {
+        /// Page-locked host memory (`CUDAHOST`, `ROCMHOST`).
+        const PINNED = 1 << 0;
+        /// CUDA managed/unified memory (`CUDAMANAGED`).
+        const MANAGED = 1 << 1;
+    }
+}
+
+impl HostAccess {
+    /// Accepts only plain CPU memory.
+    pub const STRICT: Self = Self::empty();
+
+    /// Returns whether host code may read memory on `device_type` under this
+    /// policy.
+    pub const fn allows(self, device_type: DLDeviceType) -> bool {
+        const CPU: u32 = DLDeviceType::CPU.0;
+        const CUDAHOST: u32 = DLDeviceType::CUDAHOST.0;
+        const ROCMHOST: u32 = DLDeviceType::ROCMHOST.0;
+        const CUDAMANAGED: u32 = DLDeviceType::CUDAMANAGED.0;
+
+        match device_type.0 {
+            CPU => true,
+            CUDAHOST | ROCMHOST => self.contains(Self::PINNED),
+            CUDAMANAGED => self.contains(Self::MANAGED),
+            _ => false,
+        }
+    }
+}
 
 impl DLDevice {
     /// The process-local CPU device.
@@ -29,6 +68,81 @@ impl DLDeviceType {
     }
 }
 
+/// Readable names for the device types defined by the bundled headers.
+const DEVICE_NAMES: [(DLDeviceType, &str); 16] = [
+    (DLDeviceType::CPU, "cpu"),
+    (DLDeviceType::CUDA, "cuda"),
+    (DLDeviceType::CUDAHOST, "cuda_host"),
+    (DLDeviceType::OPENCL, "opencl"),
+    (DLDeviceType::VULKAN, "vulkan"),
+    (DLDeviceType::METAL, "metal"),
+    (DLDeviceType::VPI, "vpi"),
+    (DLDeviceType::ROCM, "rocm"),
+    (DLDeviceType::ROCMHOST, "rocm_host"),
+    (DLDeviceType::EXTDEV, "ext_dev"),
+    (DLDeviceType::CUDAMANAGED, "cuda_managed"),
+    (DLDeviceType::ONEAPI, "oneapi"),
+    (DLDeviceType::WEBGPU, "webgpu"),
+    (DLDeviceType::HEXAGON, "hexagon"),
+    (DLDeviceType::MAIA, "maia"),
+    (DLDeviceType::TRN, "trn"),
+];
+
+/// Formats as a lowercase name such as `cuda` or `cuda_host`; device types
+/// not defined by the bundled headers are spelled `device<value>`.
+impl std::fmt::Display for DLDeviceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match DEVICE_NAMES
+            .iter()
+            .find(|(device_type, _)| device_type == self)
+        {
+            Some((_, name)) => f.write_str(name),
+            None => write!(f, "device{}", self.0),
+        }
+    }
+}
+
+/// Formats as `<device type>:<ordinal>`, for example `cuda:0`.
+impl std::fmt::Display for DLDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.device_type, self.device_id)
+    }
+}
+
+impl DLDevice {
+    /// Parses a name produced by the [`Display`](std::fmt::Display) impl.
+    /// A bare device type such as `cpu` selects ordinal 0.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let (device_type, device_id) = match name.split_once(':') {
+            Some((device_type, device_id)) => (device_type, device_id.parse().ok()?),
+            None => (name, 0),
+        };
+        let device_type = match DEVICE_NAMES.iter().find(|(_, name)| *name == device_type) {
+            Some((device_type, _)) => *device_type,
+            None => DLDeviceType(device_type.strip_prefix("device")?.parse().ok()?),
+        };
+        Some(Self {
+            device_type,
+            device_id,
+        })
+    }
+}
+
+impl PartialEq for DLDevice {
+    fn eq(&self, other: &Self) -> bool {
+        self.device_type == other.device_type && self.device_id == other.device_id
+    }
+}
+
+impl Eq for DLDevice {}
+
+impl std::hash::Hash for DLDevice {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(&self.device_type, state);
+        std::hash::Hash::hash(&self.device_id, state);
+    }
+}
+
 impl Default for DLDevice {
     fn default() -> Self {
         Self {
@@ -51,4 +165,49 @@ mod tests {
             assert!(!DLDeviceType(value).is_known());
         }
     }
+
+    #[test]
+    fn host_access_defaults_to_cpu_only() {
+        let policy = HostAccess::default();
+
+        assert_eq!(policy, HostAccess::STRICT);
+        assert!(policy.allows(DLDeviceType::CPU));
+        for device_type in [
+            DLDeviceType::CUDAHOST,
+            DLDeviceType::ROCMHOST,
+            DLDeviceType::CUDAMANAGED,
+            DLDeviceType::CUDA,
+        ] {
+            assert!(!policy.allows(device_type));
+        }
+    }
+
+    #[test]
+    fn host_access_opts_into_each_category_separately() {
+        assert!(HostAccess::PINNED.allows(DLDeviceType::CUDAHOST));
+        assert!(HostAccess::PINNED.allows(DLDeviceType::ROCMHOST));
+        assert!(!HostAccess::PINNED.allows(DLDeviceType::CUDAMANAGED));
+        assert!(HostAccess::MANAGED.allows(DLDeviceType::CUDAMANAGED));
+        assert!(!HostAccess::MANAGED.allows(DLDeviceType::CUDAHOST));
+        assert!(!HostAccess::all().allows(DLDeviceType::CUDA));
+        assert!(!HostAccess::all().allows(DLDeviceType::ROCM));
+    }
+
+    #[test]
+    fn device_display_names_round_trip() {
+        assert_eq!(DLDevice::CPU.to_string(), "cpu:0");
+        assert_eq!(DLDevice::cuda(3).to_string(), "cuda:3");
+        for value in [1, 2, 3, 4, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 99] {
+            let device = DLDevice {
+                device_type: DLDeviceType(value),
+                device_id: 1,
+            };
+            assert_eq!(DLDevice::from_name(&device.to_string()), Some(device));
+        }
+        assert_eq!(DLDevice::from_name("cpu"), Some(DLDevice::CPU));
+        assert_eq!(DLDevice::from_name("cuda:-1"), Some(DLDevice::cuda(-1)));
+        for name in ["", "gpu:0", "cuda:", "cuda:x", "device:0"] {
+            assert_eq!(DLDevice::from_name(name), None, "{name}");
+        }
+    }
 }
@@ -1,6 +1,10 @@
 //! Externally supplied DLPack managed tensors.
 
-use crate::{ManagedTensorBase, tensor};
+use crate::{
+    ManagedTensorBase,
+    ffi::{DLManagedTensor, DLManagedTensorVersioned},
+    tensor,
+};
 use snafu::Snafu;
 use std::{borrow::Cow, ptr::NonNull};
 
@@ -149,6 +153,38 @@ impl<M: ManagedTensorBase> Foreign<M> {
         unsafe { self.tensor().is_compact() }
     }
 
+    /// Returns an owned copy of the foreign descriptor, without the data.
+    ///
+    /// # Safety
+    ///
+    /// The foreign shape and strides pointers must reference readable metadata.
+    pub unsafe fn meta(&self) -> Result<tensor::TensorMeta, tensor::Error> {
+        unsafe { tensor::TensorMeta::from_tensor(self.tensor(), self.version(), self.flags()) }
+    }
+
+    /// Validates the foreign descriptor once and returns an owned snapshot
+    /// with derived layout facts.
+    ///
+    /// # Safety
+    ///
+    /// The foreign shape and strides pointers must reference readable metadata.
+    pub unsafe fn info(&self) -> Result<tensor::TensorInfo, tensor::Error> {
+        unsafe { tensor::TensorInfo::from_tensor(self.tensor(), self.version(), self.flags()) }
+    }
+
+    /// Returns a NumPy-style formatter for the foreign tensor contents.
+    ///
+    /// Non-CPU tensors print their metadata summary instead.
+    ///
+    /// # Safety
+    ///
+    /// The foreign metadata must be readable and, for CPU tensors, every
+    /// element addressed by the shape, strides and byte offset must be
+    /// readable and initialized while the formatter is in use.
+    pub unsafe fn display(&self) -> tensor::TensorDisplay<'_> {
+        unsafe { tensor::TensorDisplay::new(self.tensor(), self.version(), self.flags()) }
+    }
+
     /// Returns the byte-offset-adjusted typed data pointer.
     ///
     /// # Safety
@@ -192,6 +228,98 @@ impl<M: ManagedTensorBase> Foreign<M> {
     pub unsafe fn cpu_bytes(&self) -> Result<&[u8], tensor::Error> {
         unsafe { self.tensor().cpu_bytes() }
     }
+
+    /// Borrows compact host-accessible foreign data as typed elements.
+    ///
+    /// `policy` selects which non-CPU device types, such as pinned or managed
+    /// memory, are accepted in addition to CPU.
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_slice`], any device
+    /// work writing the data must have completed before it is read.
+    pub unsafe fn cpu_slice_with_policy<T: crate::DlpackElement>(
+        &self,
+        policy: crate::HostAccess,
+    ) -> Result<&[T], tensor::Error> {
+        unsafe { self.tensor().cpu_slice_with_policy::<T>(policy) }
+    }
+
+    /// Borrows compact host-accessible foreign data as bytes.
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_bytes`], any device
+    /// work writing the data must have completed before it is read.
+    pub unsafe fn cpu_bytes_with_policy(
+        &self,
+        policy: crate::HostAccess,
+    ) -> Result<&[u8], tensor::Error> {
+        unsafe { self.tensor().cpu_bytes_with_policy(policy) }
+    }
+}
+
+impl Foreign<DLManagedTensor> {
+    /// Wraps the legacy tensor as a versioned tensor without copying.
+    ///
+    /// The versioned tensor shares the descriptor, including its shape and
+    /// strides pointers. It declares the current version and carries empty
+    /// flags, because the legacy ABI cannot say whether the data is read-only
+    /// or copied. Dropping it releases the legacy tensor through its deleter.
+    pub fn into_versioned(self) -> Foreign<DLManagedTensorVersioned> {
+        // SAFETY: copying the descriptor reads only the managed tensor itself.
+        let dl_tensor = *unsafe { self.tensor() };
+        let legacy = self.into_raw();
+        let versioned = Box::new(DLManagedTensorVersioned::from_parts(
+            dl_tensor,
+            legacy.cast(),
+            Some(drop_legacy_wrapper),
+        ));
+        unsafe { Foreign::from_raw_unchecked(Box::into_raw(versioned)) }
+    }
+}
+
+unsafe extern "C" fn drop_legacy_wrapper(managed: *mut DLManagedTensorVersioned) {
+    if managed.is_null() {
+        return;
+    }
+    let managed = unsafe { Box::from_raw(managed) };
+    drop(unsafe { Foreign::<DLManagedTensor>::from_raw_unchecked(managed.manager_ctx.cast()) });
+}
+
+/// A foreign tensor of either managed tensor ABI.
+///
+/// Python extraction of this type follows the array API version
+/// negotiation, so it also accepts producers that only speak the legacy
+/// protocol.
+pub enum AnyForeign {
+    /// A legacy `DLManagedTensor`.
+    Legacy(Foreign<DLManagedTensor>),
+    /// A `DLManagedTensorVersioned`.
+    Versioned(Foreign<DLManagedTensorVersioned>),
+}
+
+impl AnyForeign {
+    /// Returns the tensor as versioned, wrapping a legacy tensor with
+    /// [`Foreign::into_versioned`].
+    pub fn into_versioned(self) -> Foreign<DLManagedTensorVersioned> {
+        match self {
+            Self::Legacy(tensor) => tensor.into_versioned(),
+            Self::Versioned(tensor) => tensor,
+        }
+    }
+}
+
+impl From<Foreign<DLManagedTensor>> for AnyForeign {
+    fn from(tensor: Foreign<DLManagedTensor>) -> Self {
+        Self::Legacy(tensor)
+    }
+}
+
+impl From<Foreign<DLManagedTensorVersioned>> for AnyForeign {
+    fn from(tensor: Foreign<DLManagedTensorVersioned>) -> Self {
+        Self::Versioned(tensor)
+    }
 }
 
 impl<M: ManagedTensorBase> Drop for Foreign<M> {
@@ -203,10 +331,7 @@ impl<M: ManagedTensorBase> Drop for Foreign<M> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        ManagedTensorBase,
-        ffi::{DLManagedTensor, DLManagedTensorVersioned},
-    };
+    use crate::{DlpackFlags, ManagedTensorBase, ffi::DLPackVersion};
     use std::sync::{
         Arc,
         atomic::{AtomicUsize, Ordering},
@@ -256,4 +381,30 @@ mod tests {
         assert!(matches!(error, FromRawError::Version { .. }));
         assert_eq!(drops.load(Ordering::Relaxed), 1);
     }
+
+    #[test]
+    fn legacy_tensor_is_wrapped_as_versioned_without_copying() {
+        let drops = Arc::new(AtomicUsize::new(0));
+        let data = vec![1_i16, 2, 3];
+        let data_ptr = data.as_ptr().cast_mut().cast();
+        let legacy = crate::allocation::fixed::make_test_tensor::<_, DLManagedTensor, 1>(
+            Box::new((data, DropCounter(Arc::clone(&drops)))),
+            data_ptr,
+            crate::ffi::DLDataType::of::<i16>(),
+            crate::ffi::DLDevice::CPU,
+            [3],
+            [1],
+            DlpackFlags::empty(),
+        )
+        .into_foreign();
+
+        let versioned = AnyForeign::from(legacy).into_versioned();
+        assert_eq!(versioned.version(), Some(DLPackVersion::default()));
+        assert_eq!(versioned.flags(), DlpackFlags::empty());
+        assert_eq!(unsafe { versioned.tensor() }.data, data_ptr);
+        assert_eq!(unsafe { versioned.cpu_slice::<i16>() }.unwrap(), &[1, 2, 3]);
+
+        drop(versioned);
+        assert_eq!(drops.load(Ordering::Relaxed), 1);
+    }
 }
@@ -4,6 +4,7 @@ use super::foreign::Foreign;
 
 use crate::DlpackElement;
 use crate::DlpackFlags;
+use crate::HostAccess;
 use crate::ManagedTensorBase;
 use crate::ffi::{DLManagedTensorVersioned, DLPackVersion};
 use crate::tensor;
@@ -111,6 +112,30 @@ where
         unsafe { self.tensor().is_compact() }
     }
 
+    /// Returns an owned copy of the descriptor, without the data.
+    pub fn meta(&self) -> Result<tensor::TensorMeta, tensor::Error> {
+        let managed: &M = self;
+        unsafe {
+            tensor::TensorMeta::from_tensor(self.tensor(), managed.version(), managed.flags())
+        }
+    }
+
+    /// Returns an owned descriptor snapshot with derived layout facts.
+    pub fn info(&self) -> Result<tensor::TensorInfo, tensor::Error> {
+        let managed: &M = self;
+        unsafe {
+            tensor::TensorInfo::from_tensor(self.tensor(), managed.version(), managed.flags())
+        }
+    }
+
+    /// Returns a NumPy-style formatter for the tensor contents.
+    ///
+    /// Non-CPU tensors print their metadata summary instead.
+    pub fn display(&self) -> tensor::TensorDisplay<'_> {
+        let managed: &M = self;
+        unsafe { tensor::TensorDisplay::new(self.tensor(), managed.version(), managed.flags()) }
+    }
+
     pub fn cpu_slice<T: DlpackElement>(&self) -> Result<&[T], tensor::Error> {
         unsafe { self.tensor().cpu_slice::<T>() }
     }
@@ -119,6 +144,32 @@ where
         unsafe { self.tensor().cpu_bytes() }
     }
 
+    /// Returns host-accessible tensor data as a typed slice.
+    ///
+    /// `policy` selects which non-CPU device types, such as pinned or managed
+    /// memory, are accepted in addition to CPU.
+    ///
+    /// # Safety
+    ///
+    /// Any device work writing the data must have completed before it is
+    /// read through the returned slice.
+    pub unsafe fn cpu_slice_with_policy<T: DlpackElement>(
+        &self,
+        policy: HostAccess,
+    ) -> Result<&[T], tensor::Error> {
+        unsafe { self.tensor().cpu_slice_with_policy::<T>(policy) }
+    }
+
+    /// Returns host-accessible tensor data as bytes.
+    ///
+    /// # Safety
+    ///
+    /// Any device work writing the data must have completed before it is
+    /// read through the returned slice.
+    pub unsafe fn cpu_bytes_with_policy(&self, policy: HostAccess) -> Result<&[u8], tensor::Error> {
+        unsafe { self.tensor().cpu_bytes_with_policy(policy) }
+    }
+
     /// Returns the CPU tensor data as a mutable typed slice, without proving exclusivity.
     ///
     /// This rejects versioned tensors carrying [`DlpackFlags::READ_ONLY`].
@@ -131,13 +182,30 @@ where
     /// `Local` alone does not prove that the producer has no aliases.
     pub unsafe fn cpu_slice_mut_unchecked<T: DlpackElement>(
         &mut self,
+    ) -> Result<&mut [T], tensor::Error> {
+        unsafe { self.cpu_slice_mut_unchecked_with_policy(HostAccess::STRICT) }
+    }
+
+    /// Returns host-accessible tensor data as a mutable typed slice, without
+    /// proving exclusivity.
+    ///
+    /// Like [`Self::cpu_slice_mut_unchecked`], but also accepts the non-CPU
+    /// device types allowed by `policy`.
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_slice_mut_unchecked`],
+    /// no device work may access the data for the returned slice's lifetime.
+    pub unsafe fn cpu_slice_mut_unchecked_with_policy<T: DlpackElement>(
+        &mut self,
+        policy: HostAccess,
     ) -> Result<&mut [T], tensor::Error> {
         if self.flags().contains(DlpackFlags::READ_ONLY) {
             return Err(tensor::Error::ReadOnly);
         }
 
         let tensor = self.tensor();
-        tensor.ensure_cpu()?;
+        tensor.ensure_host(policy)?;
         if !unsafe { tensor.is_compact()? } {
             return Err(tensor::Error::NonCompactStrides);
         }
@@ -157,12 +225,26 @@ where
     /// data for the lifetime of the returned slice. Exclusive access to this
     /// `Local` alone does not prove that the producer has no aliases.
     pub unsafe fn cpu_bytes_mut_unchecked(&mut self) -> Result<&mut [u8], tensor::Error> {
+        unsafe { self.cpu_bytes_mut_unchecked_with_policy(HostAccess::STRICT) }
+    }
+
+    /// Returns compact host-accessible storage as mutable bytes, without
+    /// proving exclusivity.
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_bytes_mut_unchecked`],
+    /// no device work may access the data for the returned slice's lifetime.
+    pub unsafe fn cpu_bytes_mut_unchecked_with_policy(
+        &mut self,
+        policy: HostAccess,
+    ) -> Result<&mut [u8], tensor::Error> {
         if self.flags().contains(DlpackFlags::READ_ONLY) {
             return Err(tensor::Error::ReadOnly);
         }
 
         let tensor = self.tensor();
-        tensor.ensure_cpu()?;
+        tensor.ensure_host(policy)?;
         if !unsafe { tensor.is_compact()? } {
             return Err(tensor::Error::NonCompactStrides);
         }
@@ -458,6 +540,70 @@ mod tests {
         assert!(matches!(error, tensor::Error::NotCpu { .. }));
     }
 
+    #[test]
+    fn host_accessible_policy_exposes_pinned_and_managed_tensors() {
+        for (device_type, policy) in [
+            (crate::ffi::DLDeviceType::CUDAHOST, HostAccess::PINNED),
+            (crate::ffi::DLDeviceType::ROCMHOST, HostAccess::PINNED),
+            (crate::ffi::DLDeviceType::CUDAMANAGED, HostAccess::MANAGED),
+        ] {
+            let device = DLDevice {
+                device_type,
+                device_id: 0,
+            };
+            let mut dlpack = dlpack_with_flags_on_device::<DLManagedTensorVersioned>(
+                DlpackFlags::IS_COPIED,
+                device,
+            );
+
+            assert!(matches!(
+                dlpack.cpu_slice::<i32>(),
+                Err(tensor::Error::NotCpu { .. })
+            ));
+            assert!(matches!(
+                dlpack.cpu_slice_mut::<i32>(),
+                Err(tensor::Error::NotCpu { .. })
+            ));
+            assert_eq!(
+                unsafe { dlpack.cpu_slice_with_policy::<i32>(policy) }.unwrap(),
+                &[1, 2, 3]
+            );
+            assert_eq!(
+                unsafe { dlpack.cpu_bytes_with_policy(policy) }
+                    .unwrap()
+                    .len(),
+                3 * size_of::<i32>()
+            );
+            unsafe { dlpack.cpu_slice_mut_unchecked_with_policy::<i32>(policy) }.unwrap()[0] = 7;
+            unsafe { dlpack.cpu_bytes_mut_unchecked_with_policy(policy) }.unwrap();
+            assert_eq!(
+                unsafe { dlpack.cpu_slice_with_policy::<i32>(policy) }.unwrap(),
+                &[7, 2, 3]
+            );
+        }
+    }
+
+    #[test]
+    fn host_accessible_policy_is_per_category() {
+        let dlpack = dlpack_with_flags_on_device::<DLManagedTensorVersioned>(
+            DlpackFlags::empty(),
+            DLDevice {
+                device_type: crate::ffi::DLDeviceType::CUDAMANAGED,
+                device_id: 0,
+            },
+        );
+
+        assert!(matches!(
+            unsafe { dlpack.cpu_slice_with_policy::<i32>(HostAccess::PINNED) },
+            Err(tensor::Error::NotCpu { .. })
+        ));
+        let foreign = dlpack.into_foreign();
+        assert_eq!(
+            unsafe { foreign.cpu_slice_with_policy::<i32>(HostAccess::all()) }.unwrap(),
+            &[1, 2, 3]
+        );
+    }
+
     #[test]
     fn flags_mut_updates_versioned_tensor() {
         let mut dlpack = dlpack_with_flags::<DLManagedTensorVersioned>(DlpackFlags::empty());
@@ -468,4 +614,50 @@ mod tests {
 
         assert_eq!(dlpack.flags(), DlpackFlags::READ_ONLY);
     }
+
+    #[test]
+    fn info_summarizes_layout_and_flags() {
+        let mut data = [0.0_f32; 6];
+        let tensor: Local<DLManagedTensorVersioned> = make_test_tensor(
+            Box::new(()),
+            data.as_mut_ptr().cast(),
+            crate::ffi::DLDataType::F32,
+            DLDevice::cuda(0),
+            [2, 3],
+            [1, 2],
+            DlpackFlags::READ_ONLY,
+        );
+
+        let info = tensor.info().unwrap();
+        assert_eq!(info.num_elements, 6);
+        assert_eq!(info.num_bytes, 24);
+        assert!(!info.is_compact);
+        assert!(info.is_fortran);
+        assert_eq!(info.meta, tensor.meta().unwrap());
+        assert_eq!(info.to_string(), "float32[2,3] cuda:0 strides=[1,2] RO");
+        assert_eq!(unsafe { tensor.into_foreign().info() }.unwrap(), info);
+    }
+
+    #[test]
+    fn info_omits_compact_strides_and_legacy_flags() {
+        let tensor = dlpack_with_flags::<DLManagedTensor>(DlpackFlags::READ_ONLY);
+
+        let info = tensor.info().unwrap();
+        assert!(info.is_compact);
+        assert!(info.is_fortran);
+        assert_eq!(info.meta.version, None);
+        assert_eq!(info.to_string(), "int32[3] cpu:0");
+    }
+
+    #[test]
+    fn display_prints_contents_or_metadata_by_device() {
+        let cpu = dlpack_with_flags::<DLManagedTensorVersioned>(DlpackFlags::empty());
+        assert_eq!(cpu.display().to_string(), "[1, 2, 3]");
+
+        let cuda = dlpack_with_flags_on_device::<DLManagedTensorVersioned>(
+            DlpackFlags::READ_ONLY,
+            DLDevice::cuda(0),
+        );
+        assert_eq!(cuda.display().to_string(), "int32[3] cuda:0 RO");
+    }
 }
@@ -0,0 +1,445 @@
+//! Crate-level error type that every module error converts into.
+
+use crate::{allocation, dlpack::FromRawError, metadata, tensor, version::VersionError};
+use snafu::Snafu;
+
+/// Any error produced by this crate.
+///
+/// Each module keeps its own precise error type; this enum wraps them
+/// transparently so application code can use `?` across modules and branch on
+/// [`Error::kind`]. With the `pyo3` feature it converts into a Python
+/// exception chosen by that kind.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(transparent)]
+    Tensor { source: tensor::Error },
+
+    #[snafu(transparent)]
+    Metadata { source: metadata::Error },
+
+    #[snafu(transparent)]
+    Allocation { source: allocation::Error },
+
+    #[snafu(transparent)]
+    Arange { source: allocation::ArangeError },
+
+    #[snafu(transparent)]
+    FromRaw { source: FromRawError },
+
+    #[snafu(transparent)]
+    Version { source: VersionError },
+
+    #[snafu(transparent)]
+    Snapshot { source: crate::snapshot::Error },
+
+    #[cfg(feature = "mmap")]
+    #[snafu(transparent)]
+    Mmap {
+        source: crate::allocation::mmap::Error,
+    },
+
+    #[cfg(all(feature = "shm", target_os = "linux"))]
+    #[snafu(transparent)]
+    Shm { source: crate::shm::Error },
+
+    #[cfg(feature = "npy")]
+    #[snafu(transparent)]
+    Npy { source: crate::npy::Error },
+
+    #[cfg(feature = "safetensors")]
+    #[snafu(transparent)]
+    Safetensors { source: crate::safetensors::Error },
+
+    #[cfg(feature = "ndarray")]
+    #[snafu(transparent)]
+    Ndarray {
+        source: crate::interop::ndarray::Error,
+    },
+
+    #[cfg(feature = "image")]
+    #[snafu(transparent)]
+    Image {
+        source: crate::interop::image::Error,
+    },
+
+    #[cfg(feature = "candle")]
+    #[snafu(transparent)]
+    Candle {
+        source: crate::interop::candle::Error,
+    },
+
+    #[cfg(feature = "cudarc")]
+    #[snafu(transparent)]
+    Cudarc {
+        source: crate::interop::cudarc::Error,
+    },
+}
+
+/// The broad category of an [`Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Shape, stride, pointer, offset or size metadata is invalid or cannot
+    /// be represented by the target.
+    Layout,
+    /// The tensor lives on a device the operation cannot access.
+    Device,
+    /// The tensor's data type does not match or is not supported.
+    DataType,
+    /// Flags forbid the requested access, such as writing a read-only tensor.
+    Access,
+    /// The producer declared an incompatible DLPack ABI version.
+    Version,
+    /// An error reported by an interop library itself.
+    Backend,
+    /// An operating-system I/O operation failed.
+    Io,
+}
+
+impl Error {
+    /// Returns the category of the underlying module error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            Self::Metadata { source } => source.kind(),
+            Self::Allocation { .. } => ErrorKind::Layout,
+            Self::Arange { source } => source.kind(),
+            Self::FromRaw { source } => source.kind(),
+            Self::Version { .. } => ErrorKind::Version,
+            Self::Snapshot { source } => source.kind(),
+            #[cfg(feature = "mmap")]
+            Self::Mmap { source } => source.kind(),
+            #[cfg(all(feature = "shm", target_os = "linux"))]
+            Self::Shm { source } => source.kind(),
+            #[cfg(feature = "npy")]
+            Self::Npy { source } => source.kind(),
+            #[cfg(feature = "safetensors")]
+            Self::Safetensors { source } => source.kind(),
+            #[cfg(feature = "ndarray")]
+            Self::Ndarray { source } => source.kind(),
+            #[cfg(feature = "image")]
+            Self::Image { source } => source.kind(),
+            #[cfg(feature = "candle")]
+            Self::Candle { source } => source.kind(),
+            #[cfg(feature = "cudarc")]
+            Self::Cudarc { source } => source.kind(),
+        }
+    }
+}
+
+impl tensor::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::NotCpu { .. } => ErrorKind::Device,
+            Self::DtypeMismatch { .. } => ErrorKind::DataType,
+            Self::ReadOnly | Self::NotCopied | Self::CannotAssertIsCopied => ErrorKind::Access,
+            _ => ErrorKind::Layout,
+        }
+    }
+}
+
+impl metadata::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        ErrorKind::Layout
+    }
+}
+
+impl allocation::ArangeError {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Metadata { source } => source.kind(),
+            Self::InvalidStep | Self::InvalidRange => ErrorKind::Layout,
+        }
+    }
+}
+
+impl FromRawError {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Null => ErrorKind::Layout,
+            Self::Version { .. } => ErrorKind::Version,
+        }
+    }
+}
+
+impl crate::snapshot::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            Self::Metadata { source } => source.kind(),
+            Self::Io { .. } => ErrorKind::Io,
+            Self::BadMagic
+            | Self::UnsupportedFormat { .. }
+            | Self::ChecksumMismatch { .. }
+            | Self::Malformed { .. } => ErrorKind::Layout,
+        }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl crate::allocation::mmap::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Metadata { source } => source.kind(),
+            Self::Io { .. } => ErrorKind::Io,
+            Self::OutOfBounds { .. } => ErrorKind::Layout,
+        }
+    }
+}
+
+#[cfg(all(feature = "shm", target_os = "linux"))]
+impl crate::shm::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            Self::Mmap { source } => source.kind(),
+            Self::Version { .. } => ErrorKind::Version,
+            Self::Io { .. } | Self::MissingFd | Self::ExtraFds => ErrorKind::Io,
+            Self::NotSealed => ErrorKind::Access,
+            Self::Decode { .. } | Self::LengthMismatch { .. } | Self::ExtentOutOfBounds { .. } => {
+                ErrorKind::Layout
+            }
+        }
+    }
+}
+
+#[cfg(feature = "npy")]
+impl crate::npy::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            Self::Metadata { source } => source.kind(),
+            Self::Mmap { source } => source.kind(),
+            Self::Io { .. } | Self::Zip { .. } => ErrorKind::Io,
+            Self::UnsupportedDescr { .. }
+            | Self::UnsupportedDtype { .. }
+            | Self::DescrMismatch { .. } => ErrorKind::DataType,
+            Self::BadMagic | Self::UnsupportedVersion { .. } | Self::Header { .. } => {
+                ErrorKind::Layout
+            }
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl crate::safetensors::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            Self::Metadata { source } => source.kind(),
+            Self::Io { .. } => ErrorKind::Io,
+            Self::Format { .. } => ErrorKind::Layout,
+            Self::UnsupportedDtype { .. } | Self::UnrepresentableDtype { .. } => {
+                ErrorKind::DataType
+            }
+        }
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl crate::interop::ndarray::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            _ => ErrorKind::Layout,
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl crate::interop::image::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Tensor { source } => source.kind(),
+            _ => ErrorKind::Layout,
+        }
+    }
+}
+
+#[cfg(feature = "candle")]
+impl crate::interop::candle::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Metadata { source } => source.kind(),
+            Self::Tensor { source } => source.kind(),
+            Self::UnsupportedDevice => ErrorKind::Device,
+            Self::UnsupportedCandleDType { .. } | Self::UnsupportedDlDataType { .. } => {
+                ErrorKind::DataType
+            }
+            Self::Candle { .. } => ErrorKind::Backend,
+            _ => ErrorKind::Layout,
+        }
+    }
+}
+
+#[cfg(feature = "cudarc")]
+impl crate::interop::cudarc::Error {
+    pub(crate) fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Metadata { source } => source.kind(),
+            Self::Tensor { source } => source.kind(),
+            Self::NotCuda { .. } | Self::DeviceIdOverflow { .. } | Self::InvalidDeviceId { .. } => {
+                ErrorKind::Device
+            }
+            Self::DtypeMismatch { .. } => ErrorKind::DataType,
+            Self::Driver { .. } => ErrorKind::Backend,
+            Self::NullData | Self::LengthOverflow { .. } => ErrorKind::Layout,
+        }
+    }
+}
+
+/// Maps layout, device and access problems to `BufferError`, dtype problems
+/// to `TypeError`, version problems to `ValueError` and backend failures to
+/// `RuntimeError`. I/O failures become `OSError`.
+#[cfg(feature = "pyo3")]
+impl From<Error> for pyo3::PyErr {
+    fn from(error: Error) -> Self {
+        use pyo3::exceptions::{
+            PyBufferError, PyOSError, PyRuntimeError, PyTypeError, PyValueError,
+        };
+
+        let message = error.to_string();
+        match error.kind() {
+            ErrorKind::Layout | ErrorKind::Device | ErrorKind::Access => {
+                PyBufferError::new_err(message)
+            }
+            ErrorKind::DataType => PyTypeError::new_err(message),
+            ErrorKind::Version => PyValueError::new_err(message),
+            ErrorKind::Backend => PyRuntimeError::new_err(message),
+            ErrorKind::Io => PyOSError::new_err(message),
+        }
+    }
+}
+
+/// Lets `?` convert module errors straight into `PyErr` inside `#[pyfunction]`s.
+macro_rules! impl_into_py_err {
+    ($($(#[$attr:meta])* $ty:ty),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            #[cfg(feature = "pyo3")]
+            impl From<$ty> for pyo3::PyErr {
+                fn from(error: $ty) -> Self {
+                    Error::from(error).into()
+                }
+            }
+        )*
+    };
+}
+
+impl_into_py_err!(
+    tensor::Error,
+    metadata::Error,
+    allocation::Error,
+    allocation::ArangeError,
+    FromRawError,
+    VersionError,
+    crate::snapshot::Error,
+    #[cfg(feature = "mmap")]
+    crate::allocation::mmap::Error,
+    #[cfg(all(feature = "shm", target_os = "linux"))]
+    crate::shm::Error,
+    #[cfg(feature = "npy")]
+    crate::npy::Error,
+    #[cfg(feature = "safetensors")]
+    crate::safetensors::Error,
+    #[cfg(feature = "ndarray")]
+    crate::interop::ndarray::Error,
+    #[cfg(feature = "image")]
+    crate::interop::image::Error,
+    #[cfg(feature = "candle")]
+    crate::interop::candle::Error,
+    #[cfg(feature = "cudarc")]
+    crate::interop::cudarc::Error,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::{DLDataType, DLDeviceType};
+
+    #[test]
+    fn module_errors_convert_with_their_category() {
+        let cases: [(Error, ErrorKind); 6] = [
+            (tensor::Error::NonCompactStrides.into(), ErrorKind::Layout),
+            (
+                tensor::Error::NotCpu {
+                    device_type: DLDeviceType::CUDA,
+                }
+                .into(),
+                ErrorKind::Device,
+            ),
+            (
+                tensor::Error::DtypeMismatch {
+                    expected: DLDataType::F32,
+                    actual: DLDataType::I32,
+                }
+                .into(),
+                ErrorKind::DataType,
+            ),
+            (tensor::Error::ReadOnly.into(), ErrorKind::Access),
+            (allocation::Error::LayoutOverflow.into(), ErrorKind::Layout),
+            (
+                FromRawError::Version {
+                    source: VersionError {
+                        expected: 1,
+                        actual: 2,
+                    },
+                }
+                .into(),
+                ErrorKind::Version,
+            ),
+        ];
+
+        for (error, kind) in cases {
+            assert_eq!(error.kind(), kind, "{error}");
+        }
+    }
+
+    #[test]
+    fn wrapping_preserves_the_module_message() {
+        let error = Error::from(tensor::Error::ReadOnly);
+        assert_eq!(error.to_string(), "tensor is read-only");
+    }
+
+    #[cfg(feature = "ndarray")]
+    #[test]
+    fn interop_errors_delegate_to_wrapped_tensor_errors() {
+        let error = Error::from(crate::interop::ndarray::Error::from(
+            tensor::Error::DtypeMismatch {
+                expected: DLDataType::F32,
+                actual: DLDataType::I32,
+            },
+        ));
+        assert_eq!(error.kind(), ErrorKind::DataType);
+    }
+
+    #[cfg(feature = "pyo3")]
+    #[test]
+    fn python_exceptions_follow_the_error_kind() {
+        use pyo3::exceptions::{PyBufferError, PyTypeError, PyValueError};
+
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| {
+            let layout = pyo3::PyErr::from(tensor::Error::NonCompactStrides);
+            assert!(layout.is_instance_of::<PyBufferError>(py));
+
+            let device = pyo3::PyErr::from(tensor::Error::NotCpu {
+                device_type: DLDeviceType::CUDA,
+            });
+            assert!(device.is_instance_of::<PyBufferError>(py));
+
+            let dtype = pyo3::PyErr::from(tensor::Error::DtypeMismatch {
+                expected: DLDataType::F32,
+                actual: DLDataType::I32,
+            });
+            assert!(dtype.is_instance_of::<PyTypeError>(py));
+
+            let version = pyo3::PyErr::from(VersionError {
+                expected: 1,
+                actual: 2,
+            });
+            assert!(version.is_instance_of::<PyValueError>(py));
+        });
+    }
+}
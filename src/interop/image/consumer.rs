@@ -1,5 +1,8 @@
 use super::*;
-use crate::{DlpackElement, Foreign, ManagedTensorBase, TryFromDlpack, tensor::is_compact_strides};
+use crate::{
+    DlpackElement, Foreign, HostAccess, ManagedTensorBase, TryFromDlpack,
+    tensor::is_compact_strides,
+};
 use image::{ImageBuffer, Pixel};
 use snafu::ensure;
 use std::{marker::PhantomData, ops::Deref};
@@ -13,15 +16,37 @@ where
     type Error = Error;
 
     unsafe fn try_from_dlpack(dlpack: &'a Foreign<M>) -> Result<Self, Self::Error> {
-        let tensor = unsafe { dlpack.tensor() };
-        let layout = validated_hwc::<P>(tensor)?;
+        unsafe { image_view_from_dlpack_with_policy(dlpack, HostAccess::STRICT) }
+    }
+}
+
+/// Borrows host-accessible HWC tensor data as an [`ImageBuffer`].
+///
+/// This is the implementation used by [`TryFromDlpack`] for borrowed image
+/// buffers, which passes [`HostAccess::STRICT`]. Other policies additionally
+/// accept pinned or managed memory.
+///
+/// # Safety
+///
+/// In addition to the [`TryFromDlpack`] requirements, any device work writing
+/// the data must have completed before the image is read.
+pub unsafe fn image_view_from_dlpack_with_policy<P, M>(
+    dlpack: &Foreign<M>,
+    policy: HostAccess,
+) -> Result<ImageBuffer<P, &[P::Subpixel]>, Error>
+where
+    P: Pixel,
+    P::Subpixel: DlpackElement,
+    M: ManagedTensorBase,
+{
+    let tensor = unsafe { dlpack.tensor() };
+    let layout = validated_hwc::<P>(tensor, policy)?;
 
-        let data_slice = unsafe {
-            std::slice::from_raw_parts(layout.data_ptr as *const P::Subpixel, layout.num_elements)
-        };
+    let data_slice = unsafe {
+        std::slice::from_raw_parts(layout.data_ptr as *const P::Subpixel, layout.num_elements)
+    };
 
-        ImageBuffer::from_raw(layout.width, layout.height, data_slice).ok_or(Error::BufferTooSmall)
-    }
+    ImageBuffer::from_raw(layout.width, layout.height, data_slice).ok_or(Error::BufferTooSmall)
 }
 
 // ---------------------------------------------------------------------------
@@ -58,21 +83,42 @@ where
     type Error = Error;
 
     unsafe fn try_from_dlpack(dlpack: Foreign<M>) -> Result<Self, Self::Error> {
-        let layout = {
-            let tensor = unsafe { dlpack.tensor() };
-            validated_hwc::<P>(tensor)?
-        };
-
-        let container = DlpackContainer {
-            dlpack,
-            data_ptr: layout.data_ptr as *const P::Subpixel,
-            num_elements: layout.num_elements,
-            _marker: PhantomData,
-        };
-        ImageBuffer::from_raw(layout.width, layout.height, container).ok_or(Error::BufferTooSmall)
+        unsafe { image_from_dlpack_with_policy(dlpack, HostAccess::STRICT) }
     }
 }
 
+/// Wraps host-accessible HWC tensor data as an owning [`ImageBuffer`].
+///
+/// This is the implementation used by [`TryFromDlpack`] for
+/// [`DlpackContainer`]-backed images, which passes [`HostAccess::STRICT`].
+///
+/// # Safety
+///
+/// In addition to the [`TryFromDlpack`] requirements, any device work writing
+/// the data must have completed before the image is read.
+pub unsafe fn image_from_dlpack_with_policy<P, M>(
+    dlpack: Foreign<M>,
+    policy: HostAccess,
+) -> Result<ImageBuffer<P, DlpackContainer<M, P::Subpixel>>, Error>
+where
+    P: Pixel,
+    P::Subpixel: DlpackElement,
+    M: ManagedTensorBase,
+{
+    let layout = {
+        let tensor = unsafe { dlpack.tensor() };
+        validated_hwc::<P>(tensor, policy)?
+    };
+
+    let container = DlpackContainer {
+        dlpack,
+        data_ptr: layout.data_ptr as *const P::Subpixel,
+        num_elements: layout.num_elements,
+        _marker: PhantomData,
+    };
+    ImageBuffer::from_raw(layout.width, layout.height, container).ok_or(Error::BufferTooSmall)
+}
+
 // ---------------------------------------------------------------------------
 // Shared validation helper
 // ---------------------------------------------------------------------------
@@ -84,7 +130,7 @@ struct HwcLayout {
     num_elements: usize,
 }
 
-fn validated_hwc<P>(tensor: &crate::ffi::DLTensor) -> Result<HwcLayout, Error>
+fn validated_hwc<P>(tensor: &crate::ffi::DLTensor, policy: HostAccess) -> Result<HwcLayout, Error>
 where
     P: Pixel,
     P::Subpixel: DlpackElement,
@@ -142,7 +188,7 @@ where
         );
     }
 
-    let data = unsafe { tensor.cpu_slice::<P::Subpixel>()? };
+    let data = unsafe { tensor.cpu_slice_with_policy::<P::Subpixel>(policy)? };
 
     Ok(HwcLayout {
         height,
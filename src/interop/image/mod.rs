@@ -22,7 +22,9 @@ use snafu::Snafu;
 mod consumer;
 mod producer;
 
-pub use consumer::DlpackContainer;
+pub use consumer::{
+    DlpackContainer, image_from_dlpack_with_policy, image_view_from_dlpack_with_policy,
+};
 
 #[derive(Debug, Snafu)]
 /// Errors produced while validating a DLPack tensor as an image buffer.
@@ -86,9 +88,9 @@ use std::ffi::c_void;
 mod tests {
     use super::*;
     use crate::{
-        Local,
+        Foreign, HostAccess, Local,
         allocation::fixed::make_test_tensor,
-        ffi::{DLManagedTensor, DLManagedTensorVersioned},
+        ffi::{DLDeviceType, DLManagedTensor, DLManagedTensorVersioned},
     };
     use image::Rgb;
 
@@ -218,6 +220,53 @@ mod tests {
         ));
     }
 
+    fn labelled_image_tensor(device_type: DLDeviceType) -> Foreign<DLManagedTensorVersioned> {
+        let data = Box::new(vec![1u8, 2, 3, 4, 5, 6]);
+        let data_ptr = data.as_ptr() as *mut c_void;
+        make_test_tensor::<_, DLManagedTensorVersioned, 3>(
+            data,
+            data_ptr,
+            u8::DTYPE,
+            DLDevice {
+                device_type,
+                device_id: 0,
+            },
+            [1, 2, 3],
+            [6, 3, 1],
+            DlpackFlags::empty(),
+        )
+        .into_foreign()
+    }
+
+    #[test]
+    fn reverse_conversion_rejects_managed_memory_by_default() {
+        let dlpack = labelled_image_tensor(DLDeviceType::CUDAMANAGED);
+
+        let err = unsafe { ImageBuffer::<Rgb<u8>, _>::try_from_dlpack(&dlpack) }.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Tensor {
+                source: crate::tensor::Error::NotCpu { .. }
+            }
+        ));
+    }
+
+    #[test]
+    fn reverse_conversion_with_policy_accepts_host_accessible_memory() {
+        let dlpack = labelled_image_tensor(DLDeviceType::CUDAMANAGED);
+        let img = unsafe {
+            image_view_from_dlpack_with_policy::<Rgb<u8>, _>(&dlpack, HostAccess::MANAGED)
+        }
+        .unwrap();
+        assert_eq!(img.get_pixel(1, 0).0, [4, 5, 6]);
+
+        let dlpack = labelled_image_tensor(DLDeviceType::CUDAHOST);
+        let img =
+            unsafe { image_from_dlpack_with_policy::<Rgb<u8>, _>(dlpack, HostAccess::PINNED) }
+                .unwrap();
+        assert_eq!(img.get_pixel(0, 0).0, [1, 2, 3]);
+    }
+
     #[test]
     fn test_reverse_conversion_rejects_non_compact_strides() {
         let data = Box::new(vec![1u8, 2, 3]);
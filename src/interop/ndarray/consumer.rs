@@ -1,5 +1,5 @@
 use super::{Error, NegativeStrideSnafu};
-use crate::{DlpackElement, DlpackFlags, Foreign, ManagedTensorBase, TryFromDlpack};
+use crate::{DlpackElement, DlpackFlags, Foreign, HostAccess, ManagedTensorBase, TryFromDlpack};
 use ndarray::{ArrayViewD, ArrayViewMutD, IxDyn, ShapeBuilder};
 use snafu::ensure;
 
@@ -11,14 +11,36 @@ where
     type Error = Error;
 
     unsafe fn try_from_dlpack(dlpack: &'a Foreign<M>) -> Result<Self, Self::Error> {
-        let tensor = unsafe { dlpack.tensor() };
-        let (shape, strides) = shape_and_strides(tensor)?;
-        let ptr = unsafe { tensor.offset_data_ptr::<T>()? };
-        validate_strided_span(&shape, &strides)?;
-        Ok(unsafe { ArrayViewD::from_shape_ptr(IxDyn(&shape).strides(IxDyn(&strides)), ptr) })
+        unsafe { array_view_from_dlpack_with_policy(dlpack, HostAccess::STRICT) }
     }
 }
 
+/// Returns an ndarray view into host-accessible DLPack tensor data.
+///
+/// This is the implementation used by [`TryFromDlpack`] for [`ArrayViewD`],
+/// which passes [`HostAccess::STRICT`]. Other policies additionally accept
+/// pinned or managed memory.
+///
+/// # Safety
+///
+/// In addition to the [`TryFromDlpack`] requirements, any device work writing
+/// the data must have completed before the view is read.
+pub unsafe fn array_view_from_dlpack_with_policy<'a, T, M>(
+    dlpack: &'a Foreign<M>,
+    policy: HostAccess,
+) -> Result<ArrayViewD<'a, T>, Error>
+where
+    T: DlpackElement,
+    M: ManagedTensorBase,
+{
+    let tensor = unsafe { dlpack.tensor() };
+    tensor.ensure_host(policy)?;
+    let (shape, strides) = shape_and_strides(tensor)?;
+    let ptr = unsafe { tensor.offset_data_ptr::<T>()? };
+    validate_strided_span(&shape, &strides)?;
+    Ok(unsafe { ArrayViewD::from_shape_ptr(IxDyn(&shape).strides(IxDyn(&strides)), ptr) })
+}
+
 impl<'a, T, M> TryFromDlpack<&'a mut Foreign<M>> for ArrayViewMutD<'a, T>
 where
     T: DlpackElement,
@@ -50,6 +72,27 @@ where
 pub unsafe fn array_view_from_dlpack_mut_unchecked<'a, T, M>(
     dlpack: &'a mut Foreign<M>,
 ) -> Result<ArrayViewMutD<'a, T>, Error>
+where
+    T: DlpackElement,
+    M: ManagedTensorBase,
+{
+    unsafe { array_view_from_dlpack_mut_unchecked_with_policy(dlpack, HostAccess::STRICT) }
+}
+
+/// Returns a mutable ndarray view into host-accessible DLPack tensor data,
+/// without proving exclusivity.
+///
+/// Like [`array_view_from_dlpack_mut_unchecked`], but also accepts the
+/// non-CPU device types allowed by `policy`.
+///
+/// # Safety
+///
+/// In addition to the requirements of [`array_view_from_dlpack_mut_unchecked`],
+/// no device work may access the data for the returned view's lifetime.
+pub unsafe fn array_view_from_dlpack_mut_unchecked_with_policy<'a, T, M>(
+    dlpack: &'a mut Foreign<M>,
+    policy: HostAccess,
+) -> Result<ArrayViewMutD<'a, T>, Error>
 where
     T: DlpackElement,
     M: ManagedTensorBase,
@@ -59,6 +102,7 @@ where
     }
 
     let tensor = unsafe { dlpack.tensor() };
+    tensor.ensure_host(policy)?;
     let (shape, strides) = shape_and_strides(tensor)?;
     validate_non_overlapping(&shape, &strides)?;
     let ptr = unsafe { tensor.offset_data_ptr::<T>()? }.cast_mut();
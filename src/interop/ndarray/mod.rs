@@ -21,7 +21,10 @@ use snafu::Snafu;
 mod consumer;
 mod producer;
 
-pub use consumer::array_view_from_dlpack_mut_unchecked;
+pub use consumer::{
+    array_view_from_dlpack_mut_unchecked, array_view_from_dlpack_mut_unchecked_with_policy,
+    array_view_from_dlpack_with_policy,
+};
 
 #[derive(Debug, Snafu)]
 /// Errors produced while validating a DLPack tensor as an ndarray view.
@@ -55,7 +58,8 @@ use ndarray::{ArrayBase, ArrayViewD, ArrayViewMutD, Dimension, OwnedRepr, ShapeB
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Local, ManagedTensorBase, allocation::fixed::make_test_tensor};
+    use crate::ffi::DLDeviceType;
+    use crate::{HostAccess, Local, ManagedTensorBase, allocation::fixed::make_test_tensor};
     use ndarray::{Array, arr2};
 
     type LegacyDlpack = Local<crate::ffi::DLManagedTensor>;
@@ -304,6 +308,72 @@ mod tests {
         );
     }
 
+    fn labelled_2x3_dlpack(device_type: DLDeviceType) -> VersionedDlpack {
+        let data = Box::new(vec![1i32, 2, 3, 4, 5, 6]);
+        let data_ptr = data.as_ptr().cast_mut().cast();
+        make_test_tensor(
+            data,
+            data_ptr,
+            <i32 as DlpackElement>::DTYPE,
+            DLDevice {
+                device_type,
+                device_id: 0,
+            },
+            [2, 3],
+            [3, 1],
+            DlpackFlags::empty(),
+        )
+    }
+
+    #[test]
+    fn ndarray_view_rejects_pinned_memory_by_default() {
+        let dlpack = labelled_2x3_dlpack(DLDeviceType::CUDAHOST).into_foreign();
+
+        assert!(matches!(
+            unsafe { ArrayViewD::<i32>::try_from_dlpack(&dlpack) },
+            Err(Error::Tensor {
+                source: crate::tensor::Error::NotCpu { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn ndarray_view_with_policy_accepts_pinned_and_managed_memory() {
+        for (device_type, policy) in [
+            (DLDeviceType::CUDAHOST, HostAccess::PINNED),
+            (DLDeviceType::ROCMHOST, HostAccess::PINNED),
+            (DLDeviceType::CUDAMANAGED, HostAccess::MANAGED),
+        ] {
+            let mut dlpack = labelled_2x3_dlpack(device_type).into_foreign();
+
+            let view =
+                unsafe { array_view_from_dlpack_with_policy::<i32, _>(&dlpack, policy) }.unwrap();
+            assert_eq!(view[[1, 2]], 6);
+
+            let mut view = unsafe {
+                array_view_from_dlpack_mut_unchecked_with_policy::<i32, _>(&mut dlpack, policy)
+            }
+            .unwrap();
+            view[[0, 0]] = 42;
+            assert_eq!(
+                unsafe { dlpack.cpu_slice_with_policy::<i32>(policy) }.unwrap()[0],
+                42
+            );
+        }
+    }
+
+    #[test]
+    fn ndarray_view_with_policy_still_rejects_device_memory() {
+        let dlpack = labelled_2x3_dlpack(DLDeviceType::CUDA).into_foreign();
+
+        assert!(matches!(
+            unsafe { array_view_from_dlpack_with_policy::<i32, _>(&dlpack, HostAccess::all()) },
+            Err(Error::Tensor {
+                source: crate::tensor::Error::NotCpu { .. }
+            })
+        ));
+    }
+
     #[test]
     fn sliced_owned_ndarray_to_dlpack_exports_non_standard_strides() {
         let array = Array::from_shape_vec((2, 2).strides((4, 2)), (0i32..7).collect()).unwrap();
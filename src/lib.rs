@@ -35,6 +35,7 @@ mod context;
 mod convert;
 mod data_type;
 mod device;
+mod error;
 mod version;
 
 /// Owning managed-tensor handles and data accessors.
@@ -43,20 +44,40 @@ pub mod dlpack;
 pub mod interop;
 
 mod managed_tensor;
+#[cfg(feature = "npy")]
+/// NumPy `.npy` and `.npz` reading and writing.
+pub mod npy;
+#[cfg(feature = "safetensors")]
+/// Loading and saving safetensors files.
+pub mod safetensors;
+#[cfg(feature = "serde")]
+mod serde_impl;
+
 #[cfg(feature = "pyo3")]
 /// Python DLPack capsule, stream, and exchange API support.
 pub mod python;
 
+#[cfg(all(feature = "shm", target_os = "linux"))]
+pub mod shm;
+
+/// Self-describing binary snapshots of CPU tensors.
+pub mod snapshot;
+
 /// Validation and data access methods for raw `DLTensor` values.
 pub mod tensor;
 
 /// Shape and stride metadata composed with managed tensor allocations.
 pub mod metadata;
 
+pub use allocation::AlignedBuffer;
+pub use allocation::scoped::{with_borrowed_dlpack, with_borrowed_dlpack_mut};
 pub use borrowed::Borrowed;
 pub use context::OpaqueContext;
 pub use convert::TryFromDlpack;
-pub use data_type::DlpackElement;
-pub use dlpack::{Foreign, Local};
+pub use data_type::{DlpackElement, NumericElement};
+pub use device::HostAccess;
+pub use dlpack::{AnyForeign, Foreign, Local};
+pub use error::{Error, ErrorKind};
 pub use managed_tensor::{DlpackFlags, ManagedTensorBase};
+pub use tensor::{TensorInfo, TensorMeta};
 pub use version::VersionError;
@@ -7,6 +7,7 @@ bitflags! {
     /// Flags carried by `DLManagedTensorVersioned`.
     #[repr(transparent)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DlpackFlags: u64 {
         /// Consumers must not modify the tensor data.
         const READ_ONLY = 1 << 0;
@@ -31,4 +31,22 @@ pub enum Error {
 
     #[snafu(display("stride value at axis {axis} does not fit in i64"))]
     StrideValueOverflow { axis: usize },
+
+    #[snafu(display("shape value at axis {axis} is negative: {value}"))]
+    NegativeShape { axis: usize, value: i64 },
+
+    #[snafu(display("stride value at axis {axis} is negative: {value}"))]
+    NegativeStride { axis: usize, value: i64 },
+
+    #[snafu(display("tensor addresses {required} elements but the buffer holds {len}"))]
+    BufferTooShort { required: usize, len: usize },
+
+    #[snafu(display("addressed element span overflows usize"))]
+    SpanOverflow,
+
+    #[snafu(display("buffer length {len} is not a multiple of the element size {element_size}"))]
+    BufferLengthNotMultiple { len: usize, element_size: usize },
+
+    #[snafu(display("buffer address {addr:#x} is not aligned to {align} bytes"))]
+    MisalignedBuffer { addr: usize, align: usize },
 }
@@ -0,0 +1,811 @@
+//! Reading and writing NumPy `.npy` files and `.npz` archives.
+//!
+//! Dtypes map to NumPy `descr` strings (`'<f4'`, `'|b1'`, `'<c16'`). NumPy
+//! has no `descr` for the [ml_dtypes] types: `numpy.save` records `bfloat16`
+//! and FP8 arrays as raw bytes (`'<V2'`, `'|V1'`), and [`write_npy`] does
+//! the same. Reading such a file back takes the intended dtype through
+//! [`read_npy_as`] or [`load_npy_as`]; `numpy.load` returns a void array
+//! that can be `.view()`ed as the ml_dtypes type. Fortran-order files keep
+//! their column-major layout as explicit strides rather than being
+//! transposed.
+//!
+//! [ml_dtypes]: https://github.com/jax-ml/ml_dtypes
+
+use crate::{
+    DlpackFlags, Foreign, HostAccess, Local, ManagedTensorBase,
+    allocation::{
+        self, AlignedBuffer,
+        mmap::{self, FileRegion},
+    },
+    ffi::{DLDataType, DLDataTypeCode, DLDevice, DLTensor},
+    metadata::{self, Copied, Dynamic},
+    tensor,
+};
+use snafu::{ResultExt, Snafu};
+use std::{
+    fs::File,
+    io::{Read, Seek, Write},
+};
+use zip::{CompressionMethod, ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+const MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// NumPy pads the header so the data starts on this boundary.
+const HEADER_ALIGNMENT: usize = 64;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(transparent)]
+    Tensor { source: tensor::Error },
+
+    #[snafu(transparent)]
+    Metadata { source: metadata::Error },
+
+    #[snafu(transparent)]
+    Mmap { source: mmap::Error },
+
+    #[snafu(display(".npy I/O failed: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display(".npz archive error: {source}"))]
+    Zip { source: zip::result::ZipError },
+
+    #[snafu(display("not a .npy file"))]
+    BadMagic,
+
+    #[snafu(display("unsupported .npy format version {major}.{minor}"))]
+    UnsupportedVersion { major: u8, minor: u8 },
+
+    #[snafu(display("malformed .npy header: {reason}"))]
+    Header { reason: String },
+
+    #[snafu(display("unsupported .npy descr {descr:?}"))]
+    UnsupportedDescr { descr: String },
+
+    #[snafu(display("dtype {dtype} has no .npy descr"))]
+    UnsupportedDtype { dtype: DLDataType },
+
+    #[snafu(display(".npy descr {descr:?} does not hold {dtype} elements"))]
+    DescrMismatch { descr: String, dtype: DLDataType },
+}
+
+impl From<allocation::Error> for Error {
+    fn from(source: allocation::Error) -> Self {
+        metadata::Error::from(source).into()
+    }
+}
+
+fn malformed(reason: impl Into<String>) -> Error {
+    Error::Header {
+        reason: reason.into(),
+    }
+}
+
+/// Reads a `.npy` stream into a compact, owned CPU tensor.
+///
+/// C-order data gets row-major strides and Fortran-order data column-major
+/// strides. Non-native byte orders are swapped while reading. The tensor is
+/// marked [`DlpackFlags::IS_COPIED`].
+pub fn read_npy<M, R>(mut reader: R) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+    R: Read,
+{
+    let (header, _) = read_header(&mut reader, None)?;
+    header.read_body(reader)
+}
+
+/// Reads a `.npy` stream holding `dtype` elements, like [`read_npy`].
+///
+/// Besides files whose `descr` already names `dtype`, this accepts raw
+/// void data of the same item size, such as the `'<V2'` NumPy records for
+/// `bfloat16` or the `'|V1'` it records for the FP8 types.
+pub fn read_npy_as<M, R>(mut reader: R, dtype: DLDataType) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+    R: Read,
+{
+    let (header, _) = read_header(&mut reader, Some(dtype))?;
+    header.read_body(reader)
+}
+
+/// Loads a `.npy` file, mapping its data instead of reading it when possible.
+///
+/// Native-endian data whose offset is aligned for the dtype is mapped
+/// privately, copy-on-write, so the tensor is writable but never writes back
+/// to the file. Anything else falls back to [`read_npy`].
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while the tensor is alive.
+pub unsafe fn load_npy<M>(file: &File) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+{
+    unsafe { load(file, None) }
+}
+
+/// Loads a `.npy` file holding `dtype` elements, like [`load_npy`]. Void
+/// data is accepted as in [`read_npy_as`].
+///
+/// # Safety
+///
+/// Same as [`load_npy`].
+pub unsafe fn load_npy_as<M>(file: &File, dtype: DLDataType) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+{
+    unsafe { load(file, Some(dtype)) }
+}
+
+unsafe fn load<M>(file: &File, dtype: Option<DLDataType>) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+{
+    let mut reader = file;
+    reader.rewind().context(IoSnafu)?;
+    let (header, offset) = read_header(&mut reader, dtype)?;
+    let align = header.dtype.element_size().clamp(1, 16) as u64;
+    if header.swap || !offset.is_multiple_of(align) {
+        return header.read_body(reader);
+    }
+
+    let region = FileRegion::new(header.dtype, header.shape.clone())
+        .with_strides(header.strides()?)
+        .with_offset(offset);
+    Ok(unsafe { Local::from_mmap_copy(file, &region) }?)
+}
+
+/// Writes a CPU tensor as `.npy`.
+///
+/// Row-major and column-major compact tensors are written as they are, with
+/// `fortran_order` set to match; any other layout, including negative
+/// strides, is gathered in C order.
+pub fn write_npy<M, W>(tensor: &Local<M>, writer: W) -> Result<(), Error>
+where
+    M: ManagedTensorBase,
+    W: Write,
+{
+    // SAFETY: `Local` descriptors and data are trusted.
+    unsafe { write_tensor(tensor.tensor(), writer) }
+}
+
+/// Writes a foreign CPU tensor as `.npy`. See [`write_npy`].
+///
+/// # Safety
+///
+/// The tensor's metadata must be readable and every element its shape and
+/// strides address must be readable host memory.
+pub unsafe fn write_foreign_npy<M, W>(tensor: &Foreign<M>, writer: W) -> Result<(), Error>
+where
+    M: ManagedTensorBase,
+    W: Write,
+{
+    unsafe { write_tensor(tensor.tensor(), writer) }
+}
+
+/// Reads every array of an `.npz` archive, in archive order.
+///
+/// Names drop the `.npy` suffix NumPy adds. Both stored (`numpy.savez`) and
+/// deflated (`numpy.savez_compressed`) members are supported.
+pub fn read_npz<M, R>(reader: R) -> Result<Vec<(String, Local<M>)>, Error>
+where
+    M: ManagedTensorBase,
+    R: Read + Seek,
+{
+    let mut archive = ZipArchive::new(reader).context(ZipSnafu)?;
+    (0..archive.len())
+        .map(|index| {
+            let entry = archive.by_index(index).context(ZipSnafu)?;
+            let name = entry.name();
+            let name = name.strip_suffix(".npy").unwrap_or(name).to_owned();
+            Ok((name, read_npy(entry)?))
+        })
+        .collect()
+}
+
+/// Writes named CPU tensors as an uncompressed `.npz` archive, like
+/// `numpy.savez`.
+pub fn write_npz<'a, M, W, I>(writer: W, tensors: I) -> Result<(), Error>
+where
+    M: ManagedTensorBase + 'a,
+    W: Write + Seek,
+    I: IntoIterator<Item = (&'a str, &'a Local<M>)>,
+{
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+    let mut archive = ZipWriter::new(writer);
+    for (name, tensor) in tensors {
+        archive
+            .start_file(format!("{name}.npy"), options)
+            .context(ZipSnafu)?;
+        write_npy(tensor, &mut archive)?;
+    }
+    archive.finish().context(ZipSnafu)?;
+    Ok(())
+}
+
+unsafe fn write_tensor<W: Write>(tensor: &DLTensor, mut writer: W) -> Result<(), Error> {
+    tensor.ensure_host(HostAccess::STRICT)?;
+    let descr = descr(tensor.dtype).ok_or(Error::UnsupportedDtype {
+        dtype: tensor.dtype,
+    })?;
+    let shape = unsafe { tensor.shape() }?;
+    let compact = unsafe { tensor.is_compact() }?;
+    let fortran_order =
+        !compact && tensor::is_fortran_strides(shape, unsafe { tensor.strides() }?)?;
+
+    writer
+        .write_all(&format_header(&descr, fortran_order, shape))
+        .context(IoSnafu)?;
+    if compact {
+        writer
+            .write_all(unsafe { tensor.cpu_bytes() }?)
+            .context(IoSnafu)?;
+    } else if fortran_order {
+        let len = unsafe { tensor.num_bytes() }?;
+        let data = unsafe { tensor.offset_bytes_ptr() }?;
+        writer
+            .write_all(unsafe { std::slice::from_raw_parts(data, len) })
+            .context(IoSnafu)?;
+    } else {
+        writer
+            .write_all(&unsafe { tensor.gather_bytes() }?)
+            .context(IoSnafu)?;
+    }
+    writer.flush().context(IoSnafu)
+}
+
+/// A parsed `.npy` header.
+#[derive(Debug)]
+struct Header {
+    dtype: DLDataType,
+    /// Whether the stored byte order differs from the host's.
+    swap: bool,
+    fortran_order: bool,
+    shape: Vec<i64>,
+}
+
+impl Header {
+    fn strides(&self) -> Result<Vec<i64>, Error> {
+        if !self.fortran_order {
+            return Ok(tensor::compact_strides(&self.shape)?);
+        }
+        let reversed: Vec<i64> = self.shape.iter().rev().copied().collect();
+        let mut strides = tensor::compact_strides(&reversed)?;
+        strides.reverse();
+        Ok(strides)
+    }
+
+    fn data_len(&self) -> Result<usize, Error> {
+        self.shape
+            .iter()
+            .try_fold(self.dtype.element_size(), |len, &dim| {
+                len.checked_mul(dim as usize)
+            })
+            .ok_or_else(|| metadata::Error::SpanOverflow.into())
+    }
+
+    fn read_body<M, R>(self, mut reader: R) -> Result<Local<M>, Error>
+    where
+        M: ManagedTensorBase,
+        R: Read,
+    {
+        let len = self.data_len()?;
+        // The shape is untrusted, so the buffer grows with the data actually
+        // read instead of being allocated from it up front.
+        let mut data = AlignedBuffer::read_exact_from(&mut reader, len).context(IoSnafu)?;
+        if self.swap {
+            swap_bytes(&mut data, self.dtype);
+        }
+
+        let data_ptr = if len == 0 {
+            std::ptr::null_mut()
+        } else {
+            data.as_mut_ptr().cast()
+        };
+        let prepared =
+            Dynamic::new(Copied(&self.shape[..]), Copied(self.strides()?)).prepare::<M>()?;
+        let mut initialized = prepared
+            .initialize(Box::new(data))
+            .map_err(metadata::Error::from)?;
+        initialized.set_data(data_ptr);
+        initialized.set_dtype(self.dtype);
+        initialized.set_device(DLDevice::CPU);
+        // SAFETY: the buffer is owned by the context.
+        initialized.set_flags_unchecked(DlpackFlags::IS_COPIED);
+        // SAFETY: the compact descriptor addresses exactly the owned buffer.
+        Ok(unsafe { initialized.finish() })
+    }
+}
+
+/// Reads the preamble and header, returning the header and the offset of
+/// the data. `dtype` overrides the element type as in [`read_npy_as`].
+fn read_header<R: Read>(reader: &mut R, dtype: Option<DLDataType>) -> Result<(Header, u64), Error> {
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble).context(IoSnafu)?;
+    if preamble[..6] != MAGIC[..] {
+        return Err(Error::BadMagic);
+    }
+    let (major, minor) = (preamble[6], preamble[7]);
+    let (len, prefix) = match major {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).context(IoSnafu)?;
+            (usize::from(u16::from_le_bytes(len)), 10)
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len).context(IoSnafu)?;
+            (u32::from_le_bytes(len) as usize, 12)
+        }
+        _ => return Err(Error::UnsupportedVersion { major, minor }),
+    };
+    let mut text = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut text)
+        .context(IoSnafu)?;
+    if text.len() != len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)).context(IoSnafu);
+    }
+    let text = std::str::from_utf8(&text).map_err(|_| malformed("header is not UTF-8"))?;
+    Ok((parse_header(text, dtype)?, (prefix + len) as u64))
+}
+
+/// Formats the preamble and padded header, using format version 1 unless the
+/// header does not fit its 16-bit length.
+fn format_header(descr: &str, fortran_order: bool, shape: &[i64]) -> Vec<u8> {
+    let shape = match shape {
+        [dim] => format!("({dim},)"),
+        _ => {
+            let dims: Vec<String> = shape.iter().map(i64::to_string).collect();
+            format!("({})", dims.join(", "))
+        }
+    };
+    let fortran_order = if fortran_order { "True" } else { "False" };
+    let text =
+        format!("{{'descr': '{descr}', 'fortran_order': {fortran_order}, 'shape': {shape}, }}");
+
+    let padded =
+        |prefix: usize| (prefix + text.len() + 1).next_multiple_of(HEADER_ALIGNMENT) - prefix;
+    let (version, len) = match padded(10) {
+        len if len <= usize::from(u16::MAX) => (1, len),
+        _ => (2, padded(12)),
+    };
+
+    let mut out = Vec::with_capacity(len + 12);
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&[version, 0]);
+    if version == 1 {
+        out.extend_from_slice(&(len as u16).to_le_bytes());
+    } else {
+        out.extend_from_slice(&(len as u32).to_le_bytes());
+    }
+    out.extend_from_slice(text.as_bytes());
+    out.resize(out.len() + len - text.len() - 1, b' ');
+    out.push(b'\n');
+    out
+}
+
+fn parse_header(text: &str, dtype: Option<DLDataType>) -> Result<Header, Error> {
+    let mut parser = Parser(text);
+    let (mut descr, mut fortran_order, mut shape) = (None, None, None);
+    parser.expect('{')?;
+    while !parser.eat('}') {
+        let key = parser.string()?;
+        parser.expect(':')?;
+        match (key, parser.value()?) {
+            ("descr", Value::Str(value)) => descr = Some(value),
+            ("fortran_order", Value::Bool(value)) => fortran_order = Some(value),
+            ("shape", Value::Tuple(value)) => shape = Some(value),
+            (key, _) => return Err(malformed(format!("unexpected entry `{key}`"))),
+        }
+        if !parser.eat(',') {
+            parser.expect('}')?;
+            break;
+        }
+    }
+
+    let descr = descr.ok_or_else(|| malformed("missing `descr`"))?;
+    let (dtype, swap) = match (parse_descr(descr), dtype) {
+        (Some(parsed), None) => parsed,
+        (Some((parsed, swap)), Some(dtype)) if parsed == dtype => (dtype, swap),
+        (None, Some(dtype)) if void_size(descr) == Some(dtype.element_size()) => (dtype, false),
+        (None, None) => {
+            return Err(Error::UnsupportedDescr {
+                descr: descr.to_owned(),
+            });
+        }
+        (_, Some(dtype)) => {
+            return Err(Error::DescrMismatch {
+                descr: descr.to_owned(),
+                dtype,
+            });
+        }
+    };
+    Ok(Header {
+        dtype,
+        swap,
+        fortran_order: fortran_order.ok_or_else(|| malformed("missing `fortran_order`"))?,
+        shape: shape.ok_or_else(|| malformed("missing `shape`"))?,
+    })
+}
+
+enum Value<'a> {
+    Str(&'a str),
+    Bool(bool),
+    Tuple(Vec<i64>),
+}
+
+/// A parser for the Python dict literal NumPy writes as the header.
+struct Parser<'a>(&'a str);
+
+impl<'a> Parser<'a> {
+    fn eat(&mut self, token: char) -> bool {
+        self.0 = self.0.trim_start();
+        match self.0.strip_prefix(token) {
+            Some(rest) => {
+                self.0 = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, token: char) -> Result<(), Error> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(malformed(format!("expected `{token}`")))
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str, Error> {
+        self.0 = self.0.trim_start();
+        let quote = match self.0.chars().next() {
+            Some(quote @ ('\'' | '"')) => quote,
+            _ => return Err(malformed("expected a string")),
+        };
+        let body = &self.0[1..];
+        let end = body
+            .find(quote)
+            .ok_or_else(|| malformed("unterminated string"))?;
+        self.0 = &body[end + 1..];
+        Ok(&body[..end])
+    }
+
+    fn value(&mut self) -> Result<Value<'a>, Error> {
+        self.0 = self.0.trim_start();
+        if self.0.starts_with(['\'', '"']) {
+            return self.string().map(Value::Str);
+        }
+        if self.0.starts_with('[') {
+            return Err(Error::UnsupportedDescr {
+                descr: "structured dtype".to_owned(),
+            });
+        }
+        for (word, value) in [("True", true), ("False", false)] {
+            if let Some(rest) = self.0.strip_prefix(word) {
+                self.0 = rest;
+                return Ok(Value::Bool(value));
+            }
+        }
+
+        self.expect('(')?;
+        let mut dims = Vec::new();
+        while !self.eat(')') {
+            self.0 = self.0.trim_start();
+            let end = self
+                .0
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(self.0.len());
+            let dim = self.0[..end]
+                .parse()
+                .map_err(|_| malformed("expected a dimension"))?;
+            self.0 = &self.0[end..];
+            // Python 2 wrote long integers with an `L` suffix.
+            self.eat('L');
+            dims.push(dim);
+            if !self.eat(',') {
+                self.expect(')')?;
+                break;
+            }
+        }
+        Ok(Value::Tuple(dims))
+    }
+}
+
+/// Whether `dtype` is an ml_dtypes type, which NumPy stores as void.
+fn is_void(dtype: DLDataType) -> bool {
+    const FLOAT8: std::ops::RangeInclusive<u8> =
+        DLDataTypeCode::FLOAT8_E3M4.0..=DLDataTypeCode::FLOAT8_E8M0FNU.0;
+    dtype == DLDataType::BF16
+        || (dtype.lanes == 1 && dtype.bits == 8 && FLOAT8.contains(&dtype.code.0))
+}
+
+/// Returns the item size of a void `descr` such as `'<V2'`.
+fn void_size(descr: &str) -> Option<usize> {
+    let size = descr
+        .strip_prefix(['<', '>', '|', '='])?
+        .strip_prefix('V')?;
+    size.parse().ok()
+}
+
+/// Parses a `descr`, returning the dtype and whether its bytes need
+/// swapping on this host.
+fn parse_descr(descr: &str) -> Option<(DLDataType, bool)> {
+    let (order, typestr) = descr.split_at_checked(1)?;
+    let (kind, size) = typestr.split_at_checked(1)?;
+    let size: u8 = size.parse().ok()?;
+    let code = match (kind, size) {
+        ("b", 1) => DLDataTypeCode::BOOL,
+        ("i", 1 | 2 | 4 | 8) => DLDataTypeCode::INT,
+        ("u", 1 | 2 | 4 | 8) => DLDataTypeCode::UINT,
+        ("f", 2 | 4 | 8) => DLDataTypeCode::FLOAT,
+        ("c", 8 | 16) => DLDataTypeCode::COMPLEX,
+        _ => return None,
+    };
+    let little = cfg!(target_endian = "little");
+    let swap = match order {
+        "<" => !little,
+        ">" => little,
+        "|" | "=" => false,
+        _ => return None,
+    };
+    Some((DLDataType::scalar(code, size * 8), swap && size > 1))
+}
+
+/// Returns the native-endian `descr` for `dtype`, if NumPy has one.
+fn descr(dtype: DLDataType) -> Option<String> {
+    let size = dtype.element_size();
+    if is_void(dtype) {
+        // `numpy.save` writes the native byte order for multi-byte void.
+        return Some(match size {
+            1 => "|V1".to_owned(),
+            _ if cfg!(target_endian = "little") => format!("<V{size}"),
+            _ => format!(">V{size}"),
+        });
+    }
+    let kind = match dtype.code {
+        DLDataTypeCode::BOOL => 'b',
+        DLDataTypeCode::INT => 'i',
+        DLDataTypeCode::UINT => 'u',
+        DLDataTypeCode::FLOAT => 'f',
+        DLDataTypeCode::COMPLEX => 'c',
+        _ => return None,
+    };
+    let order = match size {
+        1 => '|',
+        _ if cfg!(target_endian = "little") => '<',
+        _ => '>',
+    };
+    let descr = format!("{order}{kind}{size}");
+    (parse_descr(&descr)? == (dtype, false)).then_some(descr)
+}
+
+/// Reverses the bytes of every scalar in `data`; complex values swap their
+/// real and imaginary parts separately.
+fn swap_bytes(data: &mut [u8], dtype: DLDataType) {
+    let mut unit = dtype.element_size();
+    if dtype.code == DLDataTypeCode::COMPLEX {
+        unit /= 2;
+    }
+    for scalar in data.chunks_exact_mut(unit) {
+        scalar.reverse();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        allocation::fixed::make_test_tensor,
+        ffi::{DLManagedTensor, DLManagedTensorVersioned},
+    };
+    use std::io::Cursor;
+
+    type Versioned = Local<DLManagedTensorVersioned>;
+
+    /// `numpy.save` output for `np.arange(6, dtype='>i2').reshape(2, 3)`.
+    fn numpy_big_endian() -> Vec<u8> {
+        let text = "{'descr': '>i2', 'fortran_order': False, 'shape': (2, 3), }";
+        let mut file = b"\x93NUMPY\x01\x00\x76\x00".to_vec();
+        file.extend_from_slice(text.as_bytes());
+        file.resize(127, b' ');
+        file.push(b'\n');
+        for value in 0..6_i16 {
+            file.extend_from_slice(&value.to_be_bytes());
+        }
+        file
+    }
+
+    #[test]
+    fn header_matches_numpy_and_is_64_byte_aligned() {
+        let header = format_header("<f4", false, &[3]);
+        assert_eq!(header.len(), 128);
+        assert_eq!(&header[..10], b"\x93NUMPY\x01\x00\x76\x00");
+        let text = std::str::from_utf8(&header[10..]).unwrap();
+        assert!(text.starts_with("{'descr': '<f4', 'fortran_order': False, 'shape': (3,), }"));
+        assert!(text.ends_with(" \n"));
+
+        let parsed = parse_header(text, None).unwrap();
+        assert_eq!(parsed.dtype, DLDataType::F32);
+        assert_eq!(parsed.shape, [3]);
+        assert!(!parsed.fortran_order);
+
+        let scalar =
+            parse_header("{'shape': (), 'fortran_order': True, 'descr': '|b1'}", None).unwrap();
+        assert_eq!(scalar.dtype, DLDataType::BOOL);
+        assert!(scalar.shape.is_empty());
+        assert!(matches!(
+            parse_header(
+                "{'descr': [('a', '<f4')], 'fortran_order': False, 'shape': (1,)}",
+                None
+            ),
+            Err(Error::UnsupportedDescr { .. })
+        ));
+        assert!(matches!(
+            parse_header(
+                "{'descr': '<U8', 'fortran_order': False, 'shape': (1,)}",
+                None
+            ),
+            Err(Error::UnsupportedDescr { .. })
+        ));
+    }
+
+    #[test]
+    fn descrs_match_numpy_and_store_ml_dtypes_types_as_void() {
+        for dtype in [
+            DLDataType::BOOL,
+            DLDataType::U8,
+            DLDataType::I64,
+            DLDataType::F16,
+            DLDataType::C128,
+        ] {
+            let descr = descr(dtype).unwrap();
+            assert_eq!(parse_descr(&descr), Some((dtype, false)), "{descr}");
+        }
+        #[cfg(target_endian = "little")]
+        assert_eq!(descr(DLDataType::BF16).unwrap(), "<V2");
+        assert_eq!(descr(DLDataType::F8E4M3FN).unwrap(), "|V1");
+        assert_eq!(parse_descr("|V1"), None);
+        assert_eq!(descr(DLDataType::F4E2M1FN), None);
+        assert_eq!(descr(DLDataType::new(DLDataTypeCode::FLOAT, 32, 4)), None);
+    }
+
+    #[test]
+    fn void_data_reads_as_the_requested_dtype() {
+        // What `numpy.save` writes for `np.ones(2, dtype=ml_dtypes.bfloat16)`.
+        let mut file = format_header("<V2", false, &[2]);
+        file.extend_from_slice(&[0x80, 0x3f, 0x80, 0x3f]);
+
+        assert!(matches!(
+            read_npy::<DLManagedTensorVersioned, _>(file.as_slice()),
+            Err(Error::UnsupportedDescr { descr }) if descr == "<V2"
+        ));
+        let bf16: Versioned = read_npy_as(file.as_slice(), DLDataType::BF16).unwrap();
+        assert_eq!(bf16.dtype(), DLDataType::BF16);
+        assert_eq!(bf16.cpu_bytes().unwrap(), &[0x80, 0x3f, 0x80, 0x3f]);
+        assert!(matches!(
+            read_npy_as::<DLManagedTensorVersioned, _>(file.as_slice(), DLDataType::F8E5M2),
+            Err(Error::DescrMismatch { .. })
+        ));
+
+        let mut written = Vec::new();
+        write_npy(&bf16, &mut written).unwrap();
+        assert_eq!(written, file);
+
+        let floats: Versioned = Local::from_vec(vec![1.0_f32, 2.0], &[2]).unwrap();
+        let mut written = Vec::new();
+        write_npy(&floats, &mut written).unwrap();
+        let again: Versioned = read_npy_as(written.as_slice(), DLDataType::F32).unwrap();
+        assert_eq!(again.cpu_slice::<f32>().unwrap(), &[1.0, 2.0]);
+        assert!(matches!(
+            read_npy_as::<DLManagedTensorVersioned, _>(written.as_slice(), DLDataType::I32),
+            Err(Error::DescrMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn big_endian_files_are_swapped_on_read() {
+        let tensor: Versioned = read_npy(numpy_big_endian().as_slice()).unwrap();
+        assert_eq!(tensor.dtype(), DLDataType::I16);
+        assert_eq!(tensor.shape().unwrap(), &[2, 3]);
+        assert_eq!(tensor.cpu_slice::<i16>().unwrap(), &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(tensor.flags(), DlpackFlags::IS_COPIED);
+    }
+
+    #[test]
+    fn oversized_shapes_and_headers_fail_on_the_missing_data() {
+        fn truncated(result: Result<Versioned, Error>) -> bool {
+            matches!(
+                result,
+                Err(Error::Io { source }) if source.kind() == std::io::ErrorKind::UnexpectedEof
+            )
+        }
+
+        let mut huge = format_header("<f8", false, &[1 << 40]);
+        huge.extend_from_slice(&[0; 64]);
+        assert!(truncated(read_npy(huge.as_slice())));
+
+        let mut long_header = b"\x93NUMPY\x02\x00".to_vec();
+        long_header.extend_from_slice(&u32::MAX.to_le_bytes());
+        long_header.extend_from_slice(b"{'descr': '<f8'");
+        assert!(truncated(read_npy(long_header.as_slice())));
+    }
+
+    #[test]
+    fn strided_and_fortran_tensors_round_trip() {
+        // Column-major 2x3: written as-is with fortran_order.
+        let storage = [1.0_f32, 4.0, 2.0, 5.0, 3.0, 6.0];
+        let fortran = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(()),
+            storage.as_ptr().cast_mut().cast(),
+            DLDataType::F32,
+            DLDevice::CPU,
+            [2, 3],
+            [1, 2],
+            DlpackFlags::empty(),
+        );
+        let mut bytes = Vec::new();
+        write_npy(&fortran, &mut bytes).unwrap();
+        assert!(bytes.windows(20).any(|w| w == b"'fortran_order': Tru"));
+        let restored: Versioned = read_npy(bytes.as_slice()).unwrap();
+        assert_eq!(restored.strides().unwrap().unwrap(), &[1, 2]);
+        assert_eq!(restored.meta().unwrap().shape, [2, 3]);
+
+        // Every other column: gathered in C order.
+        let storage = [0_u16, 1, 2, 3, 4, 5];
+        let strided = make_test_tensor::<_, DLManagedTensor, 2>(
+            Box::new(()),
+            storage.as_ptr().cast_mut().cast(),
+            DLDataType::U16,
+            DLDevice::CPU,
+            [2, 2],
+            [3, 2],
+            DlpackFlags::empty(),
+        );
+        let mut bytes = Vec::new();
+        write_npy(&strided, &mut bytes).unwrap();
+        let restored: Versioned = read_npy(bytes.as_slice()).unwrap();
+        assert_eq!(restored.cpu_slice::<u16>().unwrap(), &[0, 2, 3, 5]);
+    }
+
+    #[test]
+    fn aligned_files_are_mapped_and_others_copied() {
+        let source = Versioned::arange(0_i32, 12, 1).unwrap();
+        let mut file = tempfile::tempfile().unwrap();
+        write_npy(&source, &mut file).unwrap();
+
+        let mut mapped: Versioned = unsafe { load_npy(&file) }.unwrap();
+        assert_eq!(mapped.byte_offset(), 128);
+        mapped.cpu_slice_mut::<i32>().unwrap()[0] = 100;
+        assert_eq!(mapped.cpu_slice::<i32>().unwrap()[..3], [100, 1, 2]);
+        drop(mapped);
+        let reread: Versioned = unsafe { load_npy(&file) }.unwrap();
+        assert_eq!(reread.cpu_slice::<i32>().unwrap()[0], 0);
+
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&numpy_big_endian()).unwrap();
+        let copied: Versioned = unsafe { load_npy(&file) }.unwrap();
+        assert_eq!(copied.byte_offset(), 0);
+        assert_eq!(copied.cpu_slice::<i16>().unwrap(), &[0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn npz_archives_keep_names_and_order() {
+        let weights = Versioned::full(&[2, 2], 0.5_f64).unwrap();
+        let bias = Versioned::zeros::<u8>(&[3]).unwrap();
+        let mut archive = Cursor::new(Vec::new());
+        write_npz(&mut archive, [("weights", &weights), ("bias", &bias)]).unwrap();
+
+        archive.rewind().unwrap();
+        let tensors: Vec<(String, Versioned)> = read_npz(archive).unwrap();
+        let names: Vec<&str> = tensors.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["weights", "bias"]);
+        assert_eq!(tensors[0].1.cpu_slice::<f64>().unwrap(), &[0.5; 4]);
+        assert_eq!(tensors[1].1.shape().unwrap(), &[3]);
+    }
+}
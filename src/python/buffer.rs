@@ -0,0 +1,372 @@
+//! Importing CPU memory that is not exported through DLPack.
+//!
+//! `bytes`, `bytearray`, `array.array`, `mmap` and older array libraries
+//! expose their memory through the buffer protocol or NumPy's
+//! `__array_interface__` rather than `__dlpack__`. Versioned extraction falls
+//! back to the buffer protocol for objects without `__dlpack__`;
+//! `__array_interface__` only hands over a raw address, so importing it is an
+//! explicit, unsafe call. The imported tensor holds the buffer, or a
+//! reference to the object that owns the memory, and its deleter releases it
+//! with the thread attached to the interpreter.
+
+use crate::{
+    DlpackFlags, Foreign, OpaqueContext,
+    ffi::{DLDataType, DLDataTypeCode, DLDevice, DLManagedTensorVersioned},
+    metadata::{Copied, Dynamic},
+    tensor::compact_strides,
+};
+use pyo3::{
+    Borrowed, Py, PyAny, PyResult, Python,
+    exceptions::{PyBufferError, PyTypeError},
+    intern,
+    types::{PyAnyMethods, PyDict, PyDictMethods, PyTypeMethods},
+};
+use std::ffi::c_void;
+
+impl Foreign<DLManagedTensorVersioned> {
+    /// Imports the memory of `ob` through the buffer protocol.
+    ///
+    /// The buffer's format must be a single native-endian scalar, and its
+    /// byte strides must be multiples of the item size. Read-only buffers
+    /// produce [`DlpackFlags::READ_ONLY`] tensors. The buffer is released
+    /// when the tensor is dropped.
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    pub fn from_buffer(ob: Borrowed<'_, '_, PyAny>) -> PyResult<Self> {
+        let buffer = pyo3::buffer::PyUntypedBuffer::get(&ob)?;
+        if buffer.suboffsets().is_some() {
+            return Err(PyBufferError::new_err(
+                "buffers with suboffsets cannot be imported",
+            ));
+        }
+        let dtype = buffer_dtype(buffer.format().to_bytes(), buffer.item_size())?;
+        let shape = buffer
+            .shape()
+            .iter()
+            .map(|&dim| dim as i64)
+            .collect::<Vec<_>>();
+        let strides = element_strides(buffer.strides(), dtype)?;
+        let data = buffer.buf_ptr();
+        let read_only = buffer.readonly();
+        wrap(
+            Box::new(buffer),
+            data,
+            dtype,
+            &shape,
+            Some(&strides),
+            read_only,
+        )
+    }
+
+    /// Imports the memory described by the `__array_interface__` of `ob`.
+    ///
+    /// The interface's `data` must be an `(address, read_only)` pair, and
+    /// masked arrays are rejected. The tensor keeps a reference to `ob`.
+    ///
+    /// # Safety
+    ///
+    /// The interface's address, shape, strides and typestr must describe
+    /// memory that `ob` keeps allocated and unmoved for as long as the tensor
+    /// exists, and that is writable unless the interface marks it read-only.
+    /// Nothing here can check this: the address is just a Python integer.
+    pub unsafe fn from_array_interface(ob: Borrowed<'_, '_, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        let interface = ob
+            .getattr(intern!(py, "__array_interface__"))?
+            .cast_into::<PyDict>()?;
+        let item = |key: &str| -> PyResult<_> {
+            Ok(interface.get_item(key)?.filter(|value| !value.is_none()))
+        };
+        let required = |key: &str| {
+            item(key)?.ok_or_else(|| {
+                PyTypeError::new_err(format!("__array_interface__ has no {key:?} entry"))
+            })
+        };
+        if item("mask")?.is_some() {
+            return Err(PyBufferError::new_err("masked arrays cannot be imported"));
+        }
+        let Ok((address, read_only)) = required("data")?.extract::<(usize, bool)>() else {
+            return Err(PyBufferError::new_err(
+                "only __array_interface__ data given as an (address, read_only) pair can be imported",
+            ));
+        };
+        let dtype = typestr_dtype(&required("typestr")?.extract::<String>()?)?;
+        let shape = required("shape")?.extract::<Vec<i64>>()?;
+        let strides = item("strides")?
+            .map(|strides| element_strides(&strides.extract::<Vec<isize>>()?, dtype))
+            .transpose()?;
+        let owner = Owner(Some(ob.to_owned().unbind()));
+        let data = std::ptr::with_exposed_provenance_mut(address);
+        wrap(
+            Box::new(owner),
+            data,
+            dtype,
+            &shape,
+            strides.as_deref(),
+            read_only,
+        )
+    }
+}
+
+/// Imports `ob`, which has no `__dlpack__`, through the buffer protocol.
+pub(crate) fn import_without_dlpack(
+    ob: Borrowed<'_, '_, PyAny>,
+) -> PyResult<Foreign<DLManagedTensorVersioned>> {
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    if unsafe { pyo3::ffi::PyObject_CheckBuffer(ob.as_ptr()) } != 0 {
+        return Foreign::from_buffer(ob);
+    }
+    Err(PyTypeError::new_err(format!(
+        "'{}' object supports neither DLPack nor the buffer protocol",
+        ob.get_type().name()?
+    )))
+}
+
+/// The object owning memory described by `__array_interface__`.
+struct Owner(Option<Py<PyAny>>);
+
+impl Drop for Owner {
+    fn drop(&mut self) {
+        if let Some(owner) = self.0.take() {
+            // After finalization, the reference is dropped without the
+            // interpreter, as PyO3 does for its own buffers.
+            let _ = Python::try_attach(move |_| drop(owner));
+        }
+    }
+}
+
+/// Builds a CPU tensor over `data`, kept alive by `ctx`.
+fn wrap<C: OpaqueContext>(
+    ctx: C,
+    data: *mut c_void,
+    dtype: DLDataType,
+    shape: &[i64],
+    strides: Option<&[i64]>,
+    read_only: bool,
+) -> PyResult<Foreign<DLManagedTensorVersioned>> {
+    let compact;
+    let strides = match strides {
+        Some(strides) => strides,
+        None => {
+            compact = compact_strides(shape)?;
+            &compact
+        }
+    };
+    let prepared =
+        Dynamic::new(Copied(shape), Copied(strides)).prepare::<DLManagedTensorVersioned>()?;
+    let mut initialized = prepared.initialize(ctx)?;
+    initialized.set_data(data);
+    initialized.set_dtype(dtype);
+    initialized.set_device(DLDevice::CPU);
+    initialized.set_flags_unchecked(if read_only {
+        DlpackFlags::READ_ONLY
+    } else {
+        DlpackFlags::empty()
+    });
+    // SAFETY: the exporter describes memory that `ctx` keeps alive.
+    Ok(unsafe { initialized.finish() }.into_foreign())
+}
+
+/// Maps a `struct` module format and item size to a scalar dtype.
+#[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+fn buffer_dtype(format: &[u8], itemsize: usize) -> PyResult<DLDataType> {
+    let unsupported = || {
+        PyBufferError::new_err(format!(
+            "unsupported buffer format {:?} with item size {itemsize}",
+            String::from_utf8_lossy(format)
+        ))
+    };
+    let spec = match format {
+        [b'@' | b'=', spec @ ..] => spec,
+        #[cfg(target_endian = "little")]
+        [b'<', spec @ ..] => spec,
+        #[cfg(target_endian = "big")]
+        [b'>' | b'!', spec @ ..] => spec,
+        spec => spec,
+    };
+    let code = match spec {
+        b"?" if itemsize == 1 => DLDataTypeCode::BOOL,
+        b"b" | b"h" | b"i" | b"l" | b"q" | b"n" => DLDataTypeCode::INT,
+        b"B" | b"c" | b"H" | b"I" | b"L" | b"Q" | b"N" => DLDataTypeCode::UINT,
+        b"e" | b"f" | b"d" => DLDataTypeCode::FLOAT,
+        b"Zf" | b"Zd" => DLDataTypeCode::COMPLEX,
+        _ => return Err(unsupported()),
+    };
+    let bits = itemsize
+        .checked_mul(8)
+        .and_then(|bits| u8::try_from(bits).ok())
+        .filter(|&bits| bits > 0)
+        .ok_or_else(unsupported)?;
+    Ok(DLDataType::scalar(code, bits))
+}
+
+/// Maps an `__array_interface__` typestr, such as `"<f4"`, to a scalar dtype.
+fn typestr_dtype(typestr: &str) -> PyResult<DLDataType> {
+    let unsupported = || {
+        PyBufferError::new_err(format!(
+            "unsupported __array_interface__ typestr {typestr:?}"
+        ))
+    };
+    let [order, kind, size @ ..] = typestr.as_bytes() else {
+        return Err(unsupported());
+    };
+    let size = std::str::from_utf8(size)
+        .ok()
+        .and_then(|size| size.parse::<u8>().ok())
+        .filter(|size| (1..32).contains(size))
+        .ok_or_else(unsupported)?;
+    let native = if cfg!(target_endian = "little") {
+        b'<'
+    } else {
+        b'>'
+    };
+    if !(*order == native || *order == b'=' || (*order == b'|' && size == 1)) {
+        return Err(unsupported());
+    }
+    let code = match kind {
+        b'b' if size == 1 => DLDataTypeCode::BOOL,
+        b'i' => DLDataTypeCode::INT,
+        b'u' => DLDataTypeCode::UINT,
+        b'f' => DLDataTypeCode::FLOAT,
+        b'c' => DLDataTypeCode::COMPLEX,
+        _ => return Err(unsupported()),
+    };
+    Ok(DLDataType::scalar(code, size * 8))
+}
+
+/// Converts byte strides to element strides of `dtype`.
+fn element_strides(byte_strides: &[isize], dtype: DLDataType) -> PyResult<Vec<i64>> {
+    let itemsize = dtype.element_size() as isize;
+    byte_strides
+        .iter()
+        .map(|&stride| {
+            if stride % itemsize == 0 {
+                Ok((stride / itemsize) as i64)
+            } else {
+                Err(PyBufferError::new_err(format!(
+                    "byte stride {stride} is not a multiple of the {dtype} item size {itemsize}"
+                )))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::{
+        Bound, PyErr,
+        conversion::FromPyObject,
+        types::{PyModule, PyTuple},
+    };
+
+    const MODULE: &std::ffi::CStr = cr#"import array
+import ctypes
+
+
+def address(buffer):
+    return ctypes.addressof((ctypes.c_char * len(buffer)).from_buffer(buffer))
+
+
+class Interface:
+    def __init__(self, buffer, typestr, shape, strides=None):
+        self.buffer = buffer
+        self.__array_interface__ = {
+            "version": 3,
+            "data": (address(buffer), False),
+            "typestr": typestr,
+            "shape": shape,
+            "strides": strides,
+        }
+
+
+def every_other():
+    return memoryview(array.array("i", range(6)))[1::2]
+
+
+def resize(buffer):
+    buffer.extend(b"\0")
+"#;
+
+    fn module(py: Python<'_>) -> PyResult<Bound<'_, PyModule>> {
+        PyModule::from_code(py, MODULE, c"buffers.py", c"buffers")
+    }
+
+    #[test]
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    fn buffers_are_imported_in_place() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let module = module(py)?;
+            let bytes = py.eval(c"bytearray(b'\\x01\\x02\\x03')", None, None)?;
+            let tensor = Foreign::<DLManagedTensorVersioned>::extract(bytes.as_borrowed())?;
+            assert_eq!(tensor.dtype(), DLDataType::U8);
+            assert_eq!(tensor.flags(), DlpackFlags::empty());
+            assert_eq!(unsafe { tensor.cpu_slice::<u8>() }?, &[1, 2, 3]);
+            // An exported bytearray cannot be resized until the tensor is
+            // dropped, which releases the buffer.
+            assert!(module.getattr("resize")?.call1((&bytes,)).is_err());
+            drop(tensor);
+            module.getattr("resize")?.call1((&bytes,))?;
+
+            let frozen = py.eval(c"b'\\x00\\x01'", None, None)?;
+            let tensor = Foreign::<DLManagedTensorVersioned>::extract(frozen.as_borrowed())?;
+            assert_eq!(tensor.flags(), DlpackFlags::READ_ONLY);
+
+            let every_other = module.getattr("every_other")?.call0()?;
+            let tensor = Foreign::<DLManagedTensorVersioned>::extract(every_other.as_borrowed())?;
+            assert_eq!(tensor.dtype(), DLDataType::I32);
+            assert_eq!(unsafe { tensor.shape() }?, &[3]);
+            assert_eq!(unsafe { tensor.strides() }?, Some(&[2][..]));
+            assert_eq!(unsafe { *tensor.offset_data_ptr::<i32>()? }, 1);
+
+            let doubles = py.eval(c"__import__('array').array('d', [1.5, 2.5])", None, None)?;
+            let typed = crate::python::PyTensorDyn::<f64>::extract(doubles.as_borrowed())?;
+            assert_eq!(typed.as_slice(), &[1.5, 2.5]);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn array_interfaces_are_imported_with_element_strides() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let module = module(py)?;
+            let interface = module.getattr("Interface")?;
+            let storage = py.eval(c"bytearray(range(8))", None, None)?;
+            let shape = PyTuple::new(py, [2, 2])?;
+            let strides = PyTuple::new(py, [2, 4])?;
+            let object = interface.call1((&storage, "<u2", shape, strides))?;
+            // SAFETY: `object` keeps `storage` alive, and nothing resizes it.
+            let tensor = unsafe { Foreign::from_array_interface(object.as_borrowed()) }?;
+            assert_eq!(tensor.dtype(), DLDataType::U16);
+            assert_eq!(unsafe { tensor.strides() }?, Some(&[1, 2][..]));
+            drop(object);
+            assert_eq!(
+                unsafe { *tensor.offset_data_ptr::<u16>()? },
+                u16::from_ne_bytes([0, 1])
+            );
+
+            let message = |result: PyResult<Foreign<DLManagedTensorVersioned>>| {
+                result.err().map(|err: PyErr| err.value(py).to_string())
+            };
+            let odd = interface.call1((&storage, "<u2", (3,), (3,)))?;
+            assert_eq!(
+                message(unsafe { Foreign::from_array_interface(odd.as_borrowed()) }).as_deref(),
+                Some("byte stride 3 is not a multiple of the uint16 item size 2")
+            );
+            let swapped = interface.call1((&storage, ">u2", (4,)))?;
+            assert_eq!(
+                message(unsafe { Foreign::from_array_interface(swapped.as_borrowed()) }).as_deref(),
+                Some("unsupported __array_interface__ typestr \">u2\"")
+            );
+            // Implicit extraction never trusts an interface's address.
+            assert_eq!(
+                message(Foreign::extract(odd.as_borrowed())).as_deref(),
+                Some("'Interface' object supports neither DLPack nor the buffer protocol")
+            );
+            Ok(())
+        })
+        .unwrap();
+    }
+}
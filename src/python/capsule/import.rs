@@ -1,13 +1,16 @@
 use super::{DLTENSOR, DLTENSOR_VERSIONED, USED_DLTENSOR, USED_DLTENSOR_VERSIONED};
 use crate::{
-    Foreign,
+    AnyForeign, Foreign,
     ffi::{DLManagedTensor, DLManagedTensorVersioned},
-    python::{DlpackStream, device::dlpack_device, exchange::DlpackExchangeApiRef},
+    python::{
+        DlpackStream, buffer::import_without_dlpack, device::dlpack_device,
+        exchange::DlpackExchangeApiRef,
+    },
 };
 use pyo3::{
     Borrowed, Bound, PyAny, PyErr,
     conversion::FromPyObject,
-    exceptions::{PyBufferError, PyRuntimeError, PyValueError},
+    exceptions::{PyBufferError, PyRuntimeError, PyTypeError, PyValueError},
     types::{PyAnyMethods, PyDict, PyString},
 };
 use std::ffi::CStr;
@@ -61,6 +64,11 @@ fn is_dlpack_capsule<'py>(ob: Borrowed<'_, 'py, PyAny>, name: &CStr, used_name:
     }
 }
 
+/// Returns whether `ob` implements `__dlpack__`.
+fn has_dlpack(ob: Borrowed<'_, '_, PyAny>) -> pyo3::PyResult<bool> {
+    ob.hasattr(PyString::intern(ob.py(), "__dlpack__"))
+}
+
 fn call_dlpack<'py>(
     ob: Borrowed<'_, 'py, PyAny>,
     max_version: Option<(u32, u32)>,
@@ -101,8 +109,7 @@ impl<'py> FromPyObject<'_, 'py> for Foreign<DLManagedTensor> {
                 "DLPack capsule pointer is unexpectedly null",
             ));
         }
-        unsafe { Self::from_raw(ptr.cast()) }
-            .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+        unsafe { Self::from_raw(ptr.cast()) }.map_err(PyErr::from)
     }
 }
 
@@ -116,6 +123,8 @@ impl<'py> FromPyObject<'_, 'py> for Foreign<DLManagedTensorVersioned> {
         let owned_capsule;
         let capsule = if is_dlpack_capsule(ob, DLTENSOR_VERSIONED, USED_DLTENSOR_VERSIONED) {
             ob.as_ptr()
+        } else if !has_dlpack(ob)? {
+            return import_without_dlpack(ob);
         } else {
             owned_capsule = call_dlpack(
                 ob,
@@ -134,9 +143,61 @@ impl<'py> FromPyObject<'_, 'py> for Foreign<DLManagedTensorVersioned> {
                 "DLPack capsule pointer is unexpectedly null",
             ));
         }
-        unsafe { Self::from_raw(ptr.cast()) }
-            .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+        unsafe { Self::from_raw(ptr.cast()) }.map_err(PyErr::from)
+    }
+}
+
+/// Follows the array API version negotiation: `__dlpack__` is called with
+/// `max_version` and, if the producer raises `TypeError`, again without
+/// arguments. Either capsule name is accepted. Objects without `__dlpack__`
+/// are imported through the buffer protocol.
+impl<'py> FromPyObject<'_, 'py> for AnyForeign {
+    type Error = PyErr;
+    fn extract(ob: Borrowed<'_, 'py, PyAny>) -> Result<Self, Self::Error> {
+        if let Some(api) = DlpackExchangeApiRef::from_object(ob)? {
+            return api
+                .managed_tensor_from_py_object_no_sync(ob)
+                .map(Self::Versioned);
+        }
+        if is_dlpack_capsule(ob, DLTENSOR_VERSIONED, USED_DLTENSOR_VERSIONED)
+            || is_dlpack_capsule(ob, DLTENSOR, USED_DLTENSOR)
+        {
+            return any_capsule_to_foreign(ob);
+        }
+        if !has_dlpack(ob)? {
+            return import_without_dlpack(ob).map(Self::Versioned);
+        }
+
+        let max_version = (
+            crate::ffi::DLPACK_MAJOR_VERSION,
+            crate::ffi::DLPACK_MINOR_VERSION,
+        );
+        let capsule = match call_dlpack(ob, Some(max_version), None, None) {
+            Ok(capsule) => capsule,
+            Err(err) if err.is_instance_of::<PyTypeError>(ob.py()) => {
+                call_dlpack(ob, None, None, None)?
+            }
+            Err(err) => return Err(err),
+        };
+        any_capsule_to_foreign(capsule.as_borrowed())
+    }
+}
+
+fn any_capsule_to_foreign(capsule: Borrowed<'_, '_, PyAny>) -> pyo3::PyResult<AnyForeign> {
+    if is_dlpack_capsule(capsule, DLTENSOR, USED_DLTENSOR) {
+        let ptr = capsule_to_raw_dlpack(capsule.as_ptr(), DLTENSOR, USED_DLTENSOR)?;
+        return unsafe { Foreign::from_raw(ptr.cast()) }
+            .map(AnyForeign::Legacy)
+            .map_err(PyErr::from);
     }
+    let ptr = capsule_to_raw_dlpack(
+        capsule.as_ptr(),
+        DLTENSOR_VERSIONED,
+        USED_DLTENSOR_VERSIONED,
+    )?;
+    unsafe { Foreign::from_raw(ptr.cast()) }
+        .map(AnyForeign::Versioned)
+        .map_err(PyErr::from)
 }
 
 impl Foreign<DLManagedTensorVersioned> {
@@ -181,8 +242,7 @@ impl Foreign<DLManagedTensorVersioned> {
                 "DLPack capsule pointer is unexpectedly null",
             ));
         }
-        unsafe { Self::from_raw(ptr.cast()) }
-            .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+        unsafe { Self::from_raw(ptr.cast()) }.map_err(PyErr::from)
     }
 
     /// Extracts a versioned DLPack tensor using an explicit consumer stream.
@@ -10,7 +10,7 @@ mod import;
 
 #[cfg(test)]
 use crate::{
-    Foreign, Local,
+    AnyForeign, Foreign, Local,
     ffi::{DLManagedTensor, DLManagedTensorVersioned},
     python::DlpackStream,
 };
@@ -359,4 +359,89 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn any_extract_negotiates_with_legacy_producers() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let module = PyModule::from_code(
+                py,
+                cr#"class Legacy:
+    def __init__(self, capsule):
+        self.capsule = capsule
+
+    def __dlpack__(self):
+        return self.capsule
+
+
+class Unversioned:
+    def __init__(self, capsule):
+        self.capsule = capsule
+        self.seen_max_version = None
+
+    def __dlpack__(self, *, max_version=None):
+        self.seen_max_version = max_version
+        return self.capsule
+"#,
+                c"negotiating_producers.py",
+                c"negotiating_producers",
+            )?;
+
+            let legacy = module
+                .getattr("Legacy")?
+                .call1((legacy_tensor().into_pyobject(py)?,))?;
+            let AnyForeign::Legacy(tensor) = AnyForeign::extract(legacy.as_borrowed())? else {
+                panic!("a legacy producer returns a legacy capsule");
+            };
+            let tensor = tensor.into_versioned();
+            assert_eq!(tensor.flags(), DlpackFlags::empty());
+            assert_eq!(
+                unsafe { tensor.tensor().cpu_slice::<i32>() }.unwrap(),
+                &[1, 2, 3]
+            );
+
+            let unversioned = module
+                .getattr("Unversioned")?
+                .call1((legacy_tensor().into_pyobject(py)?,))?;
+            let tensor = AnyForeign::extract(unversioned.as_borrowed())?;
+            assert!(matches!(tensor, AnyForeign::Legacy(_)));
+            assert!(!unversioned.getattr("seen_max_version")?.is_none());
+
+            let versioned = module
+                .getattr("Unversioned")?
+                .call1((versioned_tensor().into_pyobject(py)?,))?;
+            let tensor = AnyForeign::extract(versioned.as_borrowed())?.into_versioned();
+            assert_eq!(
+                unsafe { tensor.tensor().cpu_slice::<i32>() }.unwrap(),
+                &[4, 5, 6]
+            );
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn any_extract_accepts_either_capsule() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let legacy = legacy_tensor().into_pyobject(py)?;
+            assert!(matches!(
+                AnyForeign::extract(legacy.as_borrowed())?,
+                AnyForeign::Legacy(_)
+            ));
+            let versioned = versioned_tensor().into_pyobject(py)?;
+            assert!(matches!(
+                AnyForeign::extract(versioned.as_borrowed())?,
+                AnyForeign::Versioned(_)
+            ));
+
+            let err = match AnyForeign::extract(legacy.as_borrowed()) {
+                Ok(_) => panic!("consuming the same DLPack capsule twice should fail"),
+                Err(err) => err,
+            };
+            assert!(err.is_instance_of::<PyValueError>(py));
+            Ok(())
+        })
+        .unwrap();
+    }
 }
@@ -1,17 +1,18 @@
-use pyo3::exceptions::{PyAttributeError, PyBufferError, PyRuntimeError};
+use pyo3::exceptions::{PyAttributeError, PyBaseException, PyBufferError, PyRuntimeError};
+use pyo3::types::{PyAnyMethods, PyModule, PyType, PyTypeMethods};
 use pyo3::{Borrowed, Bound, PyAny, PyErr, PyTypeInfo, Python};
-use std::ffi::CStr;
+use std::ffi::{CStr, c_char, c_void};
 use std::ptr::NonNull;
 
 use crate::{
-    Foreign, Local,
+    Foreign, Local, TensorMeta,
     ffi::{
         DLDevice, DLManagedTensorVersioned, DLPACK_MAJOR_VERSION, DLPackExchangeAPI,
         DLPackExchangeAPIHeader, DLTensor,
     },
 };
 
-const DLPACK_EXCHANGE_API: &CStr = c"dlpack_exchange_api";
+pub(super) const DLPACK_EXCHANGE_API: &CStr = c"dlpack_exchange_api";
 
 /// Borrowed reference to a producer's `DLPackExchangeAPI` function table.
 ///
@@ -22,7 +23,24 @@ pub struct DlpackExchangeApiRef {
 }
 
 impl DlpackExchangeApiRef {
+    /// Looks up the exchange table published by the type of `obj`.
+    ///
+    /// Results, including the absence of a table, are cached per type, as
+    /// the DLPack spec allows. A type that replaces its
+    /// `__dlpack_c_exchange_api__` after the first lookup must be queried
+    /// with [`Self::from_object_uncached`].
     pub fn from_object(obj: Borrowed<'_, '_, PyAny>) -> pyo3::PyResult<Option<Self>> {
+        if let Some(api) = cache::get(unsafe { pyo3::ffi::Py_TYPE(obj.as_ptr()) }) {
+            return Ok(api.map(|api| Self { api }));
+        }
+        let api = Self::from_object_uncached(obj)?;
+        cache::insert(&obj.get_type(), api.as_ref().map(|api| api.api));
+        Ok(api)
+    }
+
+    /// Looks up the exchange table published by the type of `obj`, bypassing
+    /// the per-type cache.
+    pub fn from_object_uncached(obj: Borrowed<'_, '_, PyAny>) -> pyo3::PyResult<Option<Self>> {
         let capsule = unsafe {
             let ty = pyo3::ffi::Py_TYPE(obj.as_ptr()) as *mut pyo3::ffi::PyObject;
             let attr = pyo3::intern!(obj.py(), "__dlpack_c_exchange_api__");
@@ -57,6 +75,63 @@ impl DlpackExchangeApiRef {
         Ok(Some(Self { api }))
     }
 
+    /// Allocates a tensor through the producer's `managed_tensor_allocator`.
+    ///
+    /// Only the shape and dtype of `prototype` are used; the tensor is
+    /// allocated on `device`. This lets a kernel create its output with the
+    /// caller framework's allocator and hand it back as a native tensor with
+    /// [`Self::foreign_tensor_to_py_object_no_sync`]. An error reported
+    /// through `set_error` is raised as the named Python exception.
+    pub fn allocate(
+        &self,
+        py: Python<'_>,
+        prototype: &TensorMeta,
+        device: DLDevice,
+    ) -> pyo3::PyResult<Foreign<DLManagedTensorVersioned>> {
+        let api = unsafe { self.api.as_ref() };
+        let Some(allocator) = api.managed_tensor_allocator else {
+            return Err(PyRuntimeError::new_err(
+                "DLPackExchangeAPI managed_tensor_allocator is null",
+            ));
+        };
+        let ndim = i32::try_from(prototype.shape.len())
+            .map_err(|_| PyBufferError::new_err("prototype has too many dimensions"))?;
+
+        let mut prototype = DLTensor {
+            data: std::ptr::null_mut(),
+            device,
+            ndim,
+            dtype: prototype.dtype,
+            // The allocator only reads the prototype.
+            shape: prototype.shape.as_ptr().cast_mut(),
+            strides: std::ptr::null_mut(),
+            byte_offset: 0,
+        };
+        let mut error = None::<(String, String)>;
+        let mut out = std::ptr::null_mut();
+        let rc = unsafe {
+            allocator(
+                &mut prototype,
+                &mut out,
+                (&raw mut error).cast(),
+                Some(record_allocator_error),
+            )
+        };
+        if rc != 0 || out.is_null() {
+            if !out.is_null() {
+                drop(unsafe { Foreign::from_raw(out) });
+            }
+            return Err(match error {
+                Some((kind, message)) => allocator_error(py, &kind, message),
+                None => PyErr::take(py).unwrap_or_else(|| {
+                    PyRuntimeError::new_err("DLPackExchangeAPI allocation failed")
+                }),
+            });
+        }
+
+        unsafe { Foreign::from_raw(out) }.map_err(PyErr::from)
+    }
+
     /// Converts a Python tensor object into an owning versioned DLPack tensor.
     ///
     /// This does not perform stream synchronization. Consumers running kernels
@@ -84,8 +159,7 @@ impl DlpackExchangeApiRef {
             ));
         }
 
-        unsafe { Foreign::from_raw(out) }
-            .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+        unsafe { Foreign::from_raw(out) }.map_err(PyErr::from)
     }
 
     /// Transfers an owning managed tensor directly into a Python tensor
@@ -196,6 +270,138 @@ impl DlpackExchangeApiRef {
     }
 }
 
+/// The per-type cache of exchange table lookups.
+///
+/// Entries are keyed by the type's address and hold a weak reference to it
+/// whose callback evicts the entry when the type is collected, before its
+/// address can be reused. Lookups therefore trust a key match without
+/// resolving the reference. The lock is never held while Python code can
+/// run, which keeps it safe with and without the GIL.
+mod cache {
+    use super::DLPackExchangeAPI;
+    use pyo3::types::{PyCFunction, PyDict, PyTuple, PyTupleMethods, PyType, PyWeakrefReference};
+    use pyo3::{Bound, Py, PyResult};
+    use std::{
+        collections::HashMap,
+        hash::{BuildHasherDefault, Hasher},
+        ptr::NonNull,
+        sync::{LazyLock, Mutex, MutexGuard, PoisonError},
+    };
+
+    /// A cached table pointer, or `None` for a type without a table.
+    #[derive(Clone, Copy)]
+    struct Api(Option<NonNull<DLPackExchangeAPI>>);
+
+    // SAFETY: exchange tables are immutable and live for the process
+    // lifetime.
+    unsafe impl Send for Api {}
+
+    /// An entry lives exactly as long as its type: the weak reference's
+    /// callback removes it before the type's memory can be reused, so a key
+    /// match always names the same type.
+    struct Entry {
+        weak: Py<PyWeakrefReference>,
+        api: Api,
+    }
+
+    /// Hashes type addresses, which are already unique, by their aligned
+    /// bits.
+    #[derive(Default)]
+    struct AddressHasher(u64);
+
+    impl Hasher for AddressHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, _: &[u8]) {
+            unreachable!("only addresses are hashed")
+        }
+
+        fn write_usize(&mut self, address: usize) {
+            self.0 = (address >> 4) as u64;
+        }
+    }
+
+    type Map = HashMap<usize, Entry, BuildHasherDefault<AddressHasher>>;
+
+    static CACHE: LazyLock<Mutex<Map>> = LazyLock::new(Default::default);
+
+    fn lock() -> MutexGuard<'static, Map> {
+        CACHE.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(super) fn get(
+        ty: *mut pyo3::ffi::PyTypeObject,
+    ) -> Option<Option<NonNull<DLPackExchangeAPI>>> {
+        lock().get(&(ty as usize)).map(|entry| entry.api.0)
+    }
+
+    pub(super) fn insert(ty: &Bound<'_, PyType>, api: Option<NonNull<DLPackExchangeAPI>>) {
+        let key = ty.as_ptr() as usize;
+        let evict =
+            move |args: &Bound<'_, PyTuple>, _: Option<&Bound<'_, PyDict>>| -> PyResult<()> {
+                let weak = args.get_item(0)?;
+                let mut cache = lock();
+                let removed = cache
+                    .get(&key)
+                    .is_some_and(|entry| entry.weak.is(&weak))
+                    .then(|| cache.remove(&key));
+                drop(cache);
+                drop(removed);
+                Ok(())
+            };
+        let Ok(callback) = PyCFunction::new_closure(ty.py(), None, None, evict) else {
+            return;
+        };
+        let Ok(weak) = PyWeakrefReference::new_with(ty, callback) else {
+            return;
+        };
+        let entry = Entry {
+            weak: weak.unbind(),
+            api: Api(api),
+        };
+        // A replaced entry is dropped after the lock is released.
+        let replaced = lock().insert(key, entry);
+        drop(replaced);
+    }
+}
+
+/// The `set_error` callback passed to `managed_tensor_allocator`.
+///
+/// It only records the error, so it does not need the Python interpreter.
+unsafe extern "C" fn record_allocator_error(
+    error_ctx: *mut c_void,
+    kind: *const c_char,
+    message: *const c_char,
+) {
+    let text = |ptr: *const c_char| {
+        if ptr.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(ptr) }
+                .to_string_lossy()
+                .into_owned()
+        }
+    };
+    let error = unsafe { &mut *error_ctx.cast::<Option<(String, String)>>() };
+    *error = Some((text(kind), text(message)));
+}
+
+/// Builds the built-in exception named `kind`, or a `RuntimeError` for
+/// names that are not built-in exceptions.
+fn allocator_error(py: Python<'_>, kind: &str, message: String) -> PyErr {
+    let exception = PyModule::import(py, "builtins")
+        .and_then(|builtins| builtins.getattr(kind))
+        .ok()
+        .and_then(|ty| ty.cast_into::<PyType>().ok())
+        .filter(|ty| ty.is_subclass_of::<PyBaseException>().unwrap_or(false));
+    match exception {
+        Some(ty) => PyErr::from_type(ty, message),
+        None => PyRuntimeError::new_err(format!("{kind}: {message}")),
+    }
+}
+
 /// Walks the `prev_api` chain to find a header whose major version matches.
 ///
 /// # Safety assumption
@@ -257,11 +463,23 @@ mod tests {
     }
 
     unsafe extern "C" fn mock_allocator(
-        _prototype: *mut DLTensor,
-        _out: *mut *mut DLManagedTensorVersioned,
-        _error_ctx: *mut c_void,
-        _set_error: Option<unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char)>,
+        prototype: *mut DLTensor,
+        out: *mut *mut DLManagedTensorVersioned,
+        error_ctx: *mut c_void,
+        set_error: Option<unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char)>,
     ) -> c_int {
+        let prototype = unsafe { &*prototype };
+        let (kind, message) = match prototype.device.device_type {
+            DLDeviceType::CPU => {
+                let shape = unsafe { prototype.shape() }.unwrap();
+                let tensor = Local::<DLManagedTensorVersioned>::zeros::<f32>(shape).unwrap();
+                unsafe { *out = tensor.into_raw() };
+                return 0;
+            }
+            DLDeviceType::CUDA => (c"MemoryError", c"CUDA out of memory"),
+            _ => (c"DeviceError", c"unsupported device"),
+        };
+        unsafe { set_error.unwrap()(error_ctx, kind.as_ptr(), message.as_ptr()) };
         -1
     }
 
@@ -535,4 +753,100 @@ class MockTensor:
             assert_eq!(drops.load(Ordering::Relaxed), 1);
         });
     }
+
+    #[test]
+    fn exchange_api_allocates_through_the_producer() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let api = NonNull::new(leak_mock_api()).unwrap();
+            let api = DlpackExchangeApiRef { api };
+            let prototype = TensorMeta {
+                shape: vec![2, 3],
+                strides: None,
+                dtype: DLDataType::of::<f32>(),
+                device: DLDevice::CPU,
+                byte_offset: 0,
+                version: None,
+                flags: DlpackFlags::empty(),
+            };
+
+            let tensor = api.allocate(py, &prototype, DLDevice::CPU)?;
+            assert_eq!(unsafe { tensor.shape() }.unwrap(), &[2, 3]);
+            assert_eq!(tensor.dtype(), DLDataType::of::<f32>());
+            let object = api.foreign_tensor_to_py_object_no_sync(tensor, py)?;
+            assert_eq!(object.extract::<i64>()?, 42);
+
+            let cuda = DLDevice {
+                device_type: DLDeviceType::CUDA,
+                device_id: 0,
+            };
+            let err = api.allocate(py, &prototype, cuda).err().unwrap();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyMemoryError>(py));
+            assert_eq!(err.value(py).to_string(), "CUDA out of memory");
+
+            let metal = DLDevice {
+                device_type: DLDeviceType::METAL,
+                device_id: 0,
+            };
+            let err = api.allocate(py, &prototype, metal).err().unwrap();
+            assert!(err.is_instance_of::<PyRuntimeError>(py));
+            assert_eq!(err.value(py).to_string(), "DeviceError: unsupported device");
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn exchange_api_lookups_are_cached_per_type() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let module = PyModule::from_code(
+                py,
+                cr#"import gc
+
+
+def make(name):
+    return type(name, (), {})
+
+
+def collect():
+    gc.collect()
+"#,
+                c"cached_types.py",
+                c"cached_types",
+            )?;
+            let capsule = || unsafe {
+                let capsule = pyo3::ffi::PyCapsule_New(
+                    leak_mock_api().cast(),
+                    DLPACK_EXCHANGE_API.as_ptr(),
+                    None,
+                );
+                pyo3::Bound::from_owned_ptr(py, capsule)
+            };
+
+            let cls = module.getattr("make")?.call1(("Late",))?;
+            let obj = cls.call0()?;
+            assert!(DlpackExchangeApiRef::from_object(obj.as_borrowed())?.is_none());
+            cls.setattr("__dlpack_c_exchange_api__", capsule())?;
+            assert!(DlpackExchangeApiRef::from_object(obj.as_borrowed())?.is_none());
+            assert!(DlpackExchangeApiRef::from_object_uncached(obj.as_borrowed())?.is_some());
+
+            let old = module.getattr("make")?.call1(("Old",))?;
+            let address = old.as_ptr() as usize;
+            assert!(DlpackExchangeApiRef::from_object(old.call0()?.as_borrowed())?.is_none());
+            drop(old);
+            module.getattr("collect")?.call0()?;
+            for _ in 0..100 {
+                let new = module.getattr("make")?.call1(("New",))?;
+                new.setattr("__dlpack_c_exchange_api__", capsule())?;
+                let reused = new.as_ptr() as usize == address;
+                assert!(DlpackExchangeApiRef::from_object(new.call0()?.as_borrowed())?.is_some());
+                if reused {
+                    break;
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 //! Python DLPack protocol integration through PyO3.
 
+mod buffer;
 mod capsule;
 #[cfg(feature = "cudarc")]
 mod cudarc;
@@ -9,6 +10,10 @@ pub mod device;
 pub mod exchange;
 /// Python stream argument encoding.
 pub mod stream;
+mod tensor;
+mod typed;
 
 pub use device::dlpack_device;
 pub use stream::{DlpackStream, StreamArg};
+pub use tensor::PyDlpackTensor;
+pub use typed::{PyTensorDyn, PyTensorRef};
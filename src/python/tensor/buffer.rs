@@ -0,0 +1,354 @@
+//! The Python buffer protocol for `DlpackTensor` objects on the CPU.
+//!
+//! `memoryview`, `struct`, `bytes(...)`, Pillow and NumPy releases without
+//! DLPack support read buffers rather than capsules. A buffer view holds a
+//! reference to the `DlpackTensor` object, which owns the tensor, so the data
+//! outlives every view. The view's shape and byte strides are allocated per
+//! request and freed when the view is released.
+
+use super::PyDlpackTensor;
+use crate::{
+    DlpackFlags,
+    ffi::{DLDataType, DLDeviceType},
+    tensor::{is_compact_strides, is_fortran_strides},
+};
+use pyo3::{
+    Bound, PyResult,
+    exceptions::PyBufferError,
+    ffi::{
+        self, PyBUF_ANY_CONTIGUOUS, PyBUF_C_CONTIGUOUS, PyBUF_F_CONTIGUOUS, PyBUF_FORMAT, PyBUF_ND,
+        PyBUF_STRIDES, PyBUF_WRITABLE,
+    },
+};
+use std::{
+    ffi::{CStr, c_int},
+    ptr::NonNull,
+};
+
+/// `struct` module format strings for the scalar dtypes with one.
+const FORMATS: [(DLDataType, &CStr); 14] = [
+    (DLDataType::BOOL, c"?"),
+    (DLDataType::I8, c"b"),
+    (DLDataType::I16, c"h"),
+    (DLDataType::I32, c"i"),
+    (DLDataType::I64, c"q"),
+    (DLDataType::U8, c"B"),
+    (DLDataType::U16, c"H"),
+    (DLDataType::U32, c"I"),
+    (DLDataType::U64, c"Q"),
+    (DLDataType::F16, c"e"),
+    (DLDataType::F32, c"f"),
+    (DLDataType::F64, c"d"),
+    (DLDataType::C64, c"Zf"),
+    (DLDataType::C128, c"Zd"),
+];
+
+/// The shape and byte strides a buffer view points into.
+struct Layout {
+    shape: Box<[ffi::Py_ssize_t]>,
+    strides: Box<[ffi::Py_ssize_t]>,
+}
+
+/// Returns the buffer format string of `dtype`, if it has one.
+fn format(dtype: DLDataType) -> Option<&'static CStr> {
+    FORMATS
+        .iter()
+        .find(|(candidate, _)| *candidate == dtype)
+        .map(|&(_, format)| format)
+}
+
+/// Fills `view` with the tensor behind `slf`, as requested by `flags`.
+///
+/// # Safety
+///
+/// `view` must point to a `Py_buffer` the caller passes to
+/// [`release`] once the consumer is done with it.
+pub(super) unsafe fn fill(
+    slf: &Bound<'_, PyDlpackTensor>,
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+) -> PyResult<()> {
+    let tensor = slf.get().local();
+    let device = tensor.device();
+    if device.device_type != DLDeviceType::CPU {
+        return Err(PyBufferError::new_err(format!(
+            "cannot expose a tensor on {device} through the buffer protocol"
+        )));
+    }
+    let dtype = tensor.dtype();
+    let Some(format) = format(dtype) else {
+        return Err(PyBufferError::new_err(format!(
+            "{dtype} has no buffer protocol format"
+        )));
+    };
+    let readonly = tensor.flags().contains(DlpackFlags::READ_ONLY);
+    if readonly && flags & PyBUF_WRITABLE != 0 {
+        return Err(PyBufferError::new_err(
+            "cannot expose a read-only tensor as a writable buffer",
+        ));
+    }
+
+    let raw = tensor.tensor();
+    let shape = tensor.shape()?;
+    // SAFETY: a `Local` descriptor is valid for the object's lifetime.
+    let strides = unsafe { raw.strides_or_compact() }?;
+    let len = unsafe { raw.num_bytes() }?;
+    let c_order = is_compact_strides(shape, Some(&strides))?;
+    let f_order = is_fortran_strides(shape, Some(&strides))?;
+    // Consumers that cannot take strides expect row-major data.
+    let required = if flags & PyBUF_ANY_CONTIGUOUS == PyBUF_ANY_CONTIGUOUS {
+        Some((c_order || f_order, "contiguous"))
+    } else if flags & PyBUF_F_CONTIGUOUS == PyBUF_F_CONTIGUOUS {
+        Some((f_order, "Fortran-contiguous"))
+    } else if flags & PyBUF_C_CONTIGUOUS == PyBUF_C_CONTIGUOUS
+        || flags & PyBUF_STRIDES != PyBUF_STRIDES
+    {
+        Some((c_order, "C-contiguous"))
+    } else {
+        None
+    };
+    if let Some((false, order)) = required {
+        return Err(PyBufferError::new_err(format!(
+            "the tensor is not {order}: strides {strides:?} for shape {shape:?}"
+        )));
+    }
+
+    let itemsize = dtype.element_size();
+    let byte_strides = strides
+        .iter()
+        .map(|&stride| {
+            stride
+                .checked_mul(itemsize as i64)
+                .and_then(|stride| isize::try_from(stride).ok())
+        })
+        .collect::<Option<Box<[_]>>>()
+        .ok_or_else(|| PyBufferError::new_err("byte strides overflow isize"))?;
+    let layout = Box::new(Layout {
+        // `Local` shapes are validated non-negative and addressable.
+        shape: shape.iter().map(|&dim| dim as isize).collect(),
+        strides: byte_strides,
+    });
+    let data = if raw.data.is_null() {
+        // Empty tensors may have no allocation; buffers must not be null.
+        NonNull::<u8>::dangling().as_ptr().cast()
+    } else {
+        // SAFETY: `byte_offset` stays within the tensor's allocation.
+        unsafe { raw.data.byte_add(raw.byte_offset as usize) }
+    };
+
+    // SAFETY: the caller passes a writable `Py_buffer`.
+    let view = unsafe { &mut *view };
+    view.buf = data;
+    view.obj = slf.clone().into_any().into_ptr();
+    view.len = len as isize;
+    view.readonly = c_int::from(readonly);
+    view.itemsize = itemsize as isize;
+    view.format = if flags & PyBUF_FORMAT != 0 {
+        format.as_ptr().cast_mut()
+    } else {
+        std::ptr::null_mut()
+    };
+    view.ndim = shape.len() as c_int;
+    view.shape = if flags & PyBUF_ND == PyBUF_ND {
+        layout.shape.as_ptr().cast_mut()
+    } else {
+        std::ptr::null_mut()
+    };
+    view.strides = if flags & PyBUF_STRIDES == PyBUF_STRIDES {
+        layout.strides.as_ptr().cast_mut()
+    } else {
+        std::ptr::null_mut()
+    };
+    view.suboffsets = std::ptr::null_mut();
+    view.internal = Box::into_raw(layout).cast();
+    Ok(())
+}
+
+/// Frees the layout allocated by [`fill`].
+///
+/// # Safety
+///
+/// `view` must have been filled by [`fill`] and not released yet.
+pub(super) unsafe fn release(view: *mut ffi::Py_buffer) {
+    let view = unsafe { &mut *view };
+    let layout = std::mem::replace(&mut view.internal, std::ptr::null_mut());
+    if !layout.is_null() {
+        drop(unsafe { Box::from_raw(layout.cast::<Layout>()) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        allocation::fixed::make_test_tensor,
+        ffi::{DLDevice, DLManagedTensorVersioned},
+    };
+    use pyo3::{
+        Py, Python,
+        types::{PyAnyMethods, PyModule},
+    };
+    use std::ffi::c_void;
+
+    fn tensor<T: Send + 'static, const N: usize>(
+        py: Python<'_>,
+        data: Vec<T>,
+        dtype: DLDataType,
+        shape: [i64; N],
+        strides: [i64; N],
+        flags: DlpackFlags,
+    ) -> Py<PyDlpackTensor> {
+        let data = Box::new(data);
+        let data_ptr = data.as_ptr() as *mut c_void;
+        let tensor = make_test_tensor::<_, DLManagedTensorVersioned, N>(
+            data,
+            data_ptr,
+            dtype,
+            DLDevice::CPU,
+            shape,
+            strides,
+            flags,
+        );
+        Py::new(py, PyDlpackTensor::new(tensor)).unwrap()
+    }
+
+    #[test]
+    fn memoryviews_describe_the_tensor() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let module = PyModule::from_code(
+                py,
+                cr#"import gc
+
+
+def describe(tensor):
+    view = memoryview(tensor)
+    return view.format, view.itemsize, view.shape, view.strides, view.readonly
+
+
+def outlive(tensor):
+    view = memoryview(tensor)
+    del tensor
+    gc.collect()
+    return view.tolist()
+
+
+def write(tensor):
+    memoryview(tensor)[0, 1] = 7
+"#,
+                c"buffers.py",
+                c"buffers",
+            )?;
+            type Description = (String, usize, Vec<isize>, Vec<isize>, bool);
+            let describe = |tensor: &Py<PyDlpackTensor>| -> PyResult<Description> {
+                module.getattr("describe")?.call1((tensor,))?.extract()
+            };
+
+            let values = vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+            let row_major = tensor(
+                py,
+                values.clone(),
+                DLDataType::F32,
+                [2, 3],
+                [3, 1],
+                DlpackFlags::empty(),
+            );
+            assert_eq!(
+                describe(&row_major)?,
+                ("f".into(), 4, vec![2, 3], vec![12, 4], false)
+            );
+            let bytes = py
+                .eval(c"bytes", None, None)?
+                .call1((&row_major,))?
+                .extract::<Vec<u8>>()?;
+            assert_eq!(bytes.len(), 24);
+            module.getattr("write")?.call1((&row_major,))?;
+            // SAFETY: the memoryview that wrote the element is released.
+            let written = unsafe { row_major.get().tensor() }.cpu_slice::<f32>()?[1];
+            assert_eq!(written, 7.0);
+
+            let transposed = tensor(
+                py,
+                values,
+                DLDataType::F32,
+                [3, 2],
+                [1, 3],
+                DlpackFlags::READ_ONLY,
+            );
+            assert_eq!(
+                describe(&transposed)?,
+                ("f".into(), 4, vec![3, 2], vec![4, 12], true)
+            );
+            let rows = module
+                .getattr("outlive")?
+                .call1((transposed,))?
+                .extract::<Vec<Vec<f32>>>()?;
+            assert_eq!(rows, [[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]);
+
+            let read_only = tensor(
+                py,
+                vec![0_i64; 2],
+                DLDataType::I64,
+                [1, 2],
+                [2, 1],
+                DlpackFlags::READ_ONLY,
+            );
+            let err = module.getattr("write")?.call1((read_only,)).err().unwrap();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn formats_follow_the_struct_module() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let memoryview = py.eval(c"memoryview", None, None)?;
+            let format = |tensor: Py<PyDlpackTensor>| -> PyResult<String> {
+                memoryview.call1((tensor,))?.getattr("format")?.extract()
+            };
+            let scalar = |dtype| tensor(py, vec![0_u16], dtype, [1], [1], DlpackFlags::empty());
+            assert_eq!(format(scalar(DLDataType::F16))?, "e");
+            assert_eq!(format(scalar(DLDataType::U16))?, "H");
+            let flag = tensor(
+                py,
+                vec![1_u8],
+                DLDataType::BOOL,
+                [1],
+                [1],
+                DlpackFlags::empty(),
+            );
+            assert_eq!(format(flag)?, "?");
+
+            let err = memoryview.call1((scalar(DLDataType::BF16),)).err().unwrap();
+            assert!(err.is_instance_of::<PyBufferError>(py));
+            assert_eq!(
+                err.value(py).to_string(),
+                "bfloat16 has no buffer protocol format"
+            );
+
+            let strided = || {
+                tensor(
+                    py,
+                    vec![0_u8, 1, 2, 3, 4, 5],
+                    DLDataType::U8,
+                    [3],
+                    [2],
+                    DlpackFlags::empty(),
+                )
+            };
+            let bytes = py.eval(c"bytes", None, None)?.call1((strided(),))?;
+            assert_eq!(bytes.extract::<Vec<u8>>()?, [0, 2, 4]);
+            let unpack = py.import("struct")?.getattr("unpack_from")?;
+            let err = unpack.call1(("3B", strided())).err().unwrap();
+            assert!(err.is_instance_of::<PyBufferError>(py));
+            assert_eq!(
+                err.value(py).to_string(),
+                "the tensor is not C-contiguous: strides [2] for shape [3]"
+            );
+            Ok(())
+        })
+        .unwrap();
+    }
+}
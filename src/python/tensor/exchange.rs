@@ -0,0 +1,301 @@
+//! The `DLPackExchangeAPI` table published on the `DlpackTensor` type.
+//!
+//! Consumers that look up `__dlpack_c_exchange_api__` on the type can import
+//! and create dlpark tensors through these functions without building a
+//! capsule. dlpark tensors have no pending work, so nothing here
+//! synchronizes, and the CPU work stream is always null.
+
+use super::PyDlpackTensor;
+use crate::{
+    AlignedBuffer, ErrorKind, Foreign, Local,
+    ffi::{
+        DLDevice, DLDeviceType, DLManagedTensorVersioned, DLPACK_MAJOR_VERSION,
+        DLPACK_MINOR_VERSION, DLPackExchangeAPI, DLPackExchangeAPIHeader, DLPackVersion, DLTensor,
+    },
+    metadata::{self, Copied, Dynamic},
+    python::exchange::DLPACK_EXCHANGE_API,
+    tensor,
+};
+use pyo3::{Borrowed, Bound, IntoPyObjectExt, PyErr, PyResult, Python, types::PyCapsule};
+use std::{
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    ptr::NonNull,
+};
+
+/// The exchange table; it holds no mutable state.
+struct Table(DLPackExchangeAPI);
+
+// SAFETY: the table is immutable and its `prev_api` pointer is null.
+unsafe impl Sync for Table {}
+
+static EXCHANGE_API: Table = Table(DLPackExchangeAPI {
+    header: DLPackExchangeAPIHeader {
+        version: DLPackVersion {
+            major: DLPACK_MAJOR_VERSION,
+            minor: DLPACK_MINOR_VERSION,
+        },
+        prev_api: std::ptr::null_mut(),
+    },
+    managed_tensor_allocator: Some(managed_tensor_allocator),
+    managed_tensor_from_py_object_no_sync: Some(managed_tensor_from_py_object_no_sync),
+    managed_tensor_to_py_object_no_sync: Some(managed_tensor_to_py_object_no_sync),
+    dltensor_from_py_object_no_sync: Some(dltensor_from_py_object_no_sync),
+    current_work_stream: Some(current_work_stream),
+});
+
+/// Returns the table in a `"dlpack_exchange_api"` capsule.
+pub(super) fn capsule(py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+    let api = NonNull::from(&EXCHANGE_API.0).cast::<c_void>();
+    // SAFETY: the table is static, and consumers only read it.
+    let capsule =
+        unsafe { pyo3::ffi::PyCapsule_New(api.as_ptr(), DLPACK_EXCHANGE_API.as_ptr(), None) };
+    unsafe { Bound::from_owned_ptr_or_err(py, capsule) }?
+        .cast_into::<PyCapsule>()
+        .map_err(PyErr::from)
+}
+
+/// Allocates a zeroed, compact CPU tensor shaped like `prototype`.
+fn allocate(prototype: &DLTensor) -> Result<Local<DLManagedTensorVersioned>, crate::Error> {
+    if prototype.device.device_type != DLDeviceType::CPU {
+        return Err(tensor::Error::NotCpu {
+            device_type: prototype.device.device_type,
+        }
+        .into());
+    }
+    // SAFETY: the caller passes a prototype with a readable shape.
+    let shape = unsafe { prototype.shape() }?;
+    let len = unsafe { prototype.num_bytes() }?;
+    let strides = tensor::compact_strides(shape)?;
+    let mut data = AlignedBuffer::<u8>::zeroed(len)?;
+    let data_ptr = if len == 0 {
+        std::ptr::null_mut()
+    } else {
+        data.as_mut_ptr().cast()
+    };
+    let prepared = Dynamic::new(Copied(shape), Copied(strides)).prepare()?;
+    let mut initialized = prepared
+        .initialize(Box::new(data))
+        .map_err(metadata::Error::from)?;
+    initialized.set_data(data_ptr);
+    initialized.set_dtype(prototype.dtype);
+    initialized.set_device(DLDevice::CPU);
+    // The flags stay empty: this is a fresh output, not a copy of producer
+    // data.
+    // SAFETY: the compact descriptor addresses exactly the owned buffer.
+    Ok(unsafe { initialized.finish() })
+}
+
+/// The Python exception name matching [`crate::Error`]'s `PyErr` conversion.
+fn exception_name(kind: ErrorKind) -> &'static CStr {
+    match kind {
+        ErrorKind::Layout | ErrorKind::Device | ErrorKind::Access => c"BufferError",
+        ErrorKind::DataType => c"TypeError",
+        ErrorKind::Version => c"ValueError",
+        ErrorKind::Backend => c"RuntimeError",
+        ErrorKind::Io => c"OSError",
+    }
+}
+
+unsafe extern "C" fn managed_tensor_allocator(
+    prototype: *mut DLTensor,
+    out: *mut *mut DLManagedTensorVersioned,
+    error_ctx: *mut c_void,
+    set_error: Option<unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char)>,
+) -> c_int {
+    match allocate(unsafe { &*prototype }) {
+        Ok(tensor) => {
+            unsafe { *out = tensor.into_raw() };
+            0
+        }
+        Err(error) => {
+            if let Some(set_error) = set_error {
+                let message = CString::new(error.to_string()).unwrap_or_default();
+                let kind = exception_name(error.kind());
+                unsafe { set_error(error_ctx, kind.as_ptr(), message.as_ptr()) };
+            }
+            -1
+        }
+    }
+}
+
+/// Runs `f` on the `DlpackTensor` behind `py_object`, setting the Python
+/// error indicator on failure.
+///
+/// # Safety
+///
+/// The thread must be attached, and `py_object` must be a live object.
+unsafe fn with_tensor(
+    py_object: *mut c_void,
+    f: impl FnOnce(&PyDlpackTensor) -> PyResult<()>,
+) -> c_int {
+    let py = unsafe { Python::assume_attached() };
+    let object = unsafe { Borrowed::from_ptr(py, py_object.cast()) };
+    let result = object
+        .cast::<PyDlpackTensor>()
+        .map_err(PyErr::from)
+        .and_then(|tensor| f(tensor.get()));
+    match result {
+        Ok(()) => 0,
+        Err(error) => {
+            error.restore(py);
+            -1
+        }
+    }
+}
+
+unsafe extern "C" fn managed_tensor_from_py_object_no_sync(
+    py_object: *mut c_void,
+    out: *mut *mut DLManagedTensorVersioned,
+) -> c_int {
+    unsafe {
+        with_tensor(py_object, |tensor| {
+            *out = tensor.export::<DLManagedTensorVersioned>()?.into_raw();
+            Ok(())
+        })
+    }
+}
+
+unsafe extern "C" fn dltensor_from_py_object_no_sync(
+    py_object: *mut c_void,
+    out: *mut DLTensor,
+) -> c_int {
+    unsafe {
+        with_tensor(py_object, |tensor| {
+            *out = *tensor.tensor().tensor();
+            Ok(())
+        })
+    }
+}
+
+unsafe extern "C" fn managed_tensor_to_py_object_no_sync(
+    tensor: *mut DLManagedTensorVersioned,
+    out_py_object: *mut *mut c_void,
+) -> c_int {
+    let py = unsafe { Python::assume_attached() };
+    let result = unsafe { Foreign::from_raw(tensor) }
+        .map_err(PyErr::from)
+        .and_then(|tensor| unsafe { PyDlpackTensor::from_foreign(tensor) })
+        .and_then(|tensor| tensor.into_bound_py_any(py));
+    match result {
+        Ok(object) => {
+            unsafe { *out_py_object = object.into_ptr().cast() };
+            0
+        }
+        Err(error) => {
+            error.restore(py);
+            -1
+        }
+    }
+}
+
+unsafe extern "C" fn current_work_stream(
+    _device_type: DLDeviceType,
+    _device_id: i32,
+    out_current_stream: *mut *mut c_void,
+) -> c_int {
+    unsafe { *out_current_stream = std::ptr::null_mut() };
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DlpackFlags, ffi::DLDataType, python::exchange::DlpackExchangeApiRef};
+    use pyo3::{Py, conversion::FromPyObject};
+
+    fn tensor() -> PyDlpackTensor {
+        let data = vec![1_u16, 2, 3, 4];
+        let data_ptr = data.as_ptr() as *mut c_void;
+        crate::allocation::fixed::make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(data),
+            data_ptr,
+            DLDataType::of::<u16>(),
+            DLDevice::CPU,
+            [2, 2],
+            [2, 1],
+            DlpackFlags::READ_ONLY,
+        )
+        .into()
+    }
+
+    #[test]
+    fn consumers_take_the_fast_path() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> PyResult<()> {
+            let object = Py::new(py, tensor())?.into_bound(py).into_any();
+            let api = DlpackExchangeApiRef::from_object(object.as_borrowed())?.unwrap();
+            assert!(api.current_work_stream(DLDevice::CPU)?.is_null());
+            api.with_dltensor_view_no_sync(object.as_borrowed(), |view| {
+                assert_eq!(unsafe { view.cpu_slice::<u16>() }.unwrap(), &[1, 2, 3, 4]);
+            })?;
+
+            let imported = Foreign::<DLManagedTensorVersioned>::extract(object.as_borrowed())?;
+            assert_eq!(imported.flags(), DlpackFlags::READ_ONLY);
+            assert_eq!(unsafe { imported.shape() }.unwrap(), &[2, 2]);
+
+            let round_trip = api.foreign_tensor_to_py_object_no_sync(imported, py)?;
+            let round_trip = round_trip.cast::<PyDlpackTensor>()?.get();
+            assert_eq!(round_trip.local().flags(), DlpackFlags::READ_ONLY);
+            assert_eq!(
+                unsafe { round_trip.tensor() }.cpu_slice::<u16>().unwrap(),
+                &[1, 2, 3, 4]
+            );
+
+            let err = match api.managed_tensor_from_py_object_no_sync(py.None().bind_borrowed(py)) {
+                Ok(_) => panic!("only DlpackTensor objects can be imported"),
+                Err(err) => err,
+            };
+            assert!(err.is_instance_of::<pyo3::exceptions::PyTypeError>(py));
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    unsafe extern "C" fn record_error(
+        ctx: *mut c_void,
+        kind: *const c_char,
+        message: *const c_char,
+    ) {
+        let (kind, message) = unsafe { (CStr::from_ptr(kind), CStr::from_ptr(message)) };
+        let recorded = unsafe { &mut *ctx.cast::<Option<String>>() };
+        *recorded = Some(format!(
+            "{}: {}",
+            kind.to_str().unwrap(),
+            message.to_str().unwrap()
+        ));
+    }
+
+    #[test]
+    fn allocator_creates_zeroed_cpu_tensors() {
+        let allocator = EXCHANGE_API.0.managed_tensor_allocator.unwrap();
+        let mut shape = [2_i64, 3];
+        let mut prototype = DLTensor {
+            dtype: DLDataType::of::<f32>(),
+            device: DLDevice::CPU,
+            ndim: 2,
+            shape: shape.as_mut_ptr(),
+            ..DLTensor::default()
+        };
+        let mut error = None::<String>;
+        let error_ctx = (&raw mut error).cast();
+
+        let mut out = std::ptr::null_mut();
+        let rc = unsafe { allocator(&mut prototype, &mut out, error_ctx, Some(record_error)) };
+        assert_eq!(rc, 0);
+        let allocated = unsafe { Foreign::from_raw(out) }.unwrap();
+        assert_eq!(allocated.flags(), DlpackFlags::empty());
+        assert_eq!(unsafe { allocated.shape() }.unwrap(), &[2, 3]);
+        assert_eq!(
+            unsafe { allocated.tensor().cpu_slice::<f32>() }.unwrap(),
+            &[0.0; 6]
+        );
+
+        prototype.device = DLDevice {
+            device_type: DLDeviceType::CUDA,
+            device_id: 0,
+        };
+        let rc = unsafe { allocator(&mut prototype, &mut out, error_ctx, Some(record_error)) };
+        assert_eq!(rc, -1);
+        assert!(error.unwrap().starts_with("BufferError: "));
+    }
+}
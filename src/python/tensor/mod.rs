@@ -0,0 +1,445 @@
+//! A Python tensor object implementing the DLPack producer protocol.
+
+#[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+mod buffer;
+mod exchange;
+mod negotiate;
+
+use crate::{
+    DlpackFlags, Foreign, Local, ManagedTensorBase, OpaqueContext, TensorInfo,
+    ffi::{DLDevice, DLManagedTensor, DLManagedTensorVersioned, DLTensor},
+    metadata::{Copied, Dynamic},
+    tensor,
+};
+use negotiate::{Export, negotiate};
+use pyo3::{
+    Bound, IntoPyObjectExt, PyAny, PyResult, Python,
+    exceptions::PyBufferError,
+    pyclass, pymethods,
+    types::{PyCapsule, PyTuple},
+};
+use std::sync::Arc;
+
+/// The tensor shared by a [`PyDlpackTensor`] and every export made from it.
+struct Shared(Local<DLManagedTensorVersioned>);
+
+// SAFETY: every `Local` is initialized with an `OpaqueContext`, which is
+// `Send`, so its deleter may run on any thread. The descriptor is never
+// mutated once shared.
+unsafe impl Send for Shared {}
+unsafe impl Sync for Shared {}
+
+/// A tensor exposed to Python through `__dlpack__` and `__dlpack_device__`.
+///
+/// Returning a [`Local`] from a `#[pyfunction]` produces a bare capsule,
+/// which `from_dlpack` consumers such as `torch.from_dlpack`,
+/// `numpy.from_dlpack` and `jax.dlpack.from_dlpack` do not accept. Wrapping
+/// it in this class gives Python an object that follows the array API
+/// producer protocol instead.
+///
+/// Each `__dlpack__` call exports a new zero-copy view that keeps the owned
+/// tensor alive, so the object can be consumed any number of times. Views
+/// never carry [`DlpackFlags::IS_COPIED`], because their data is shared.
+/// Consumers passing `copy=True` instead receive an owned copy that does.
+///
+/// The type also publishes a `__dlpack_c_exchange_api__` table, so consumers
+/// that support the DLPack C exchange API, including
+/// [`DlpackExchangeApiRef`](super::exchange::DlpackExchangeApiRef), import
+/// it without a capsule.
+///
+/// CPU tensors with a `struct` format, which covers every standard scalar
+/// dtype except `bfloat16` and the narrow float formats, also implement the
+/// buffer protocol, so `memoryview`, `bytes` and other buffer consumers read
+/// them in place. Read-only tensors produce read-only buffers. Builds against
+/// the stable ABI need Python 3.11 or later for this.
+#[pyclass(name = "DlpackTensor", module = "dlpark", frozen)]
+pub struct PyDlpackTensor {
+    tensor: Arc<Shared>,
+}
+
+impl PyDlpackTensor {
+    /// Wraps a locally produced tensor.
+    pub fn new(tensor: Local<DLManagedTensorVersioned>) -> Self {
+        Self {
+            tensor: Arc::new(Shared(tensor)),
+        }
+    }
+
+    /// Returns the wrapped tensor.
+    ///
+    /// # Safety
+    ///
+    /// Python code can write the data through writable buffers and DLPack
+    /// views at any time, so [`Local`]'s safe slice accessors do not hold
+    /// here. The caller must not read or write the data while Python can
+    /// write it, for example while another thread runs Python code or an
+    /// export is alive. [`Self::info`] reads the metadata safely.
+    pub unsafe fn tensor(&self) -> &Local<DLManagedTensorVersioned> {
+        self.local()
+    }
+
+    /// Returns a snapshot of the wrapped tensor's metadata.
+    pub fn info(&self) -> Result<TensorInfo, tensor::Error> {
+        self.local().info()
+    }
+
+    /// The wrapped tensor, for its metadata only; see [`Self::tensor`].
+    fn local(&self) -> &Local<DLManagedTensorVersioned> {
+        &self.tensor.0
+    }
+
+    /// Wraps a tensor received from another producer, taking ownership.
+    ///
+    /// The tensor keeps its flags, and its shape and strides are copied into
+    /// a local descriptor.
+    ///
+    /// # Safety
+    ///
+    /// The descriptor must be valid: `shape` and `strides` must be readable
+    /// and `data` must address the elements they describe until the tensor's
+    /// deleter runs, which may happen on any thread.
+    pub unsafe fn from_foreign(tensor: Foreign<DLManagedTensorVersioned>) -> PyResult<Self> {
+        let raw = *unsafe { tensor.tensor() };
+        let flags = tensor.flags();
+        // SAFETY: the context owns the foreign tensor, so its metadata and
+        // data stay valid for the view's lifetime.
+        let local = unsafe { view(&raw, flags, Box::new(Adopted { _tensor: tensor })) }?;
+        Ok(Self::new(local))
+    }
+
+    /// Exports a zero-copy view that keeps the wrapped tensor alive.
+    fn export<M: ManagedTensorBase>(&self) -> PyResult<Local<M>> {
+        self.export_on(self.local().device())
+    }
+
+    /// Exports a zero-copy view labelled with `device`, on which the data
+    /// must be accessible.
+    fn export_on<M: ManagedTensorBase>(&self, device: DLDevice) -> PyResult<Local<M>> {
+        let source = self.local();
+        let mut raw = *source.tensor();
+        raw.device = device;
+        // SAFETY: the view addresses the same elements as the wrapped tensor,
+        // which its context keeps alive.
+        unsafe {
+            view(
+                &raw,
+                source.flags() - DlpackFlags::IS_COPIED,
+                Arc::clone(&self.tensor),
+            )
+        }
+    }
+
+    /// Exports what `__dlpack__` negotiated with the consumer.
+    fn export_negotiated<M: ManagedTensorBase>(&self, export: Export) -> PyResult<Local<M>> {
+        match export {
+            Export::View(device) => self.export_on(device),
+            // SAFETY: negotiation only copies host-accessible tensors, and
+            // the wrapped tensor has no pending device work.
+            Export::Copy => unsafe { negotiate::deep_copy(self.local().tensor()) },
+        }
+    }
+}
+
+/// A foreign tensor owned by the context of a local view.
+struct Adopted {
+    _tensor: Foreign<DLManagedTensorVersioned>,
+}
+
+// SAFETY: `PyDlpackTensor::from_foreign` requires the deleter to be callable
+// from any thread.
+unsafe impl Send for Adopted {}
+
+/// Builds a tensor with the metadata of `raw`, owning its data through `ctx`.
+///
+/// # Safety
+///
+/// `raw` must be a valid descriptor whose data `ctx` keeps alive.
+unsafe fn view<M, C>(raw: &DLTensor, flags: DlpackFlags, ctx: C) -> PyResult<Local<M>>
+where
+    M: ManagedTensorBase,
+    C: OpaqueContext,
+{
+    let shape = unsafe { raw.shape() }?;
+    let strides = unsafe { raw.strides_or_compact() }?;
+    let prepared = Dynamic::new(Copied(shape), Copied(&strides[..])).prepare::<M>()?;
+    let mut initialized = prepared.initialize(ctx)?;
+    initialized.set_data(raw.data);
+    initialized.set_byte_offset(raw.byte_offset);
+    initialized.set_dtype(raw.dtype);
+    initialized.set_device(raw.device);
+    initialized.set_flags_unchecked(flags);
+    Ok(unsafe { initialized.finish() })
+}
+
+impl From<Local<DLManagedTensorVersioned>> for PyDlpackTensor {
+    fn from(tensor: Local<DLManagedTensorVersioned>) -> Self {
+        Self::new(tensor)
+    }
+}
+
+#[pymethods]
+impl PyDlpackTensor {
+    /// The DLPack C exchange API table for this type.
+    #[classattr]
+    fn __dlpack_c_exchange_api__(py: Python<'_>) -> PyResult<Bound<'_, PyCapsule>> {
+        exchange::capsule(py)
+    }
+
+    /// Exports the tensor as a DLPack capsule.
+    ///
+    /// A versioned capsule is returned when `max_version` has a major version
+    /// of at least 1, and a legacy capsule otherwise. Read-only tensors cannot
+    /// be exported as zero-copy legacy capsules, which have no way to signal
+    /// it. `stream` is accepted and ignored: a wrapped tensor has no pending
+    /// work.
+    ///
+    /// `copy=True` exports a compact CPU copy marked as copied, which only
+    /// host-accessible tensors support. Otherwise the export is a view, and
+    /// `dl_device` may name the tensor's own device or, for host-accessible
+    /// memory, `(kDLCPU, 0)`. Requests that cannot be honoured, including
+    /// `copy=False` when a copy would be needed, raise `BufferError`.
+    #[pyo3(signature = (*, stream = None, max_version = None, dl_device = None, copy = None))]
+    fn __dlpack__<'py>(
+        &self,
+        py: Python<'py>,
+        stream: Option<Bound<'py, PyAny>>,
+        max_version: Option<(u32, u32)>,
+        dl_device: Option<(u32, i32)>,
+        copy: Option<bool>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let _ = stream;
+        let export = negotiate(self.local().device(), dl_device, copy)?;
+        match max_version {
+            Some((major, _)) if major >= 1 => self
+                .export_negotiated::<DLManagedTensorVersioned>(export)?
+                .into_bound_py_any(py),
+            _ => {
+                if export != Export::Copy && self.local().flags().contains(DlpackFlags::READ_ONLY) {
+                    return Err(PyBufferError::new_err(
+                        "cannot export a read-only tensor as a legacy DLPack capsule",
+                    ));
+                }
+                self.export_negotiated::<DLManagedTensor>(export)?
+                    .into_bound_py_any(py)
+            }
+        }
+    }
+
+    /// Exposes the tensor through the buffer protocol.
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut pyo3::ffi::Py_buffer,
+        flags: std::ffi::c_int,
+    ) -> PyResult<()> {
+        // SAFETY: Python passes a `Py_buffer` it releases through
+        // `__releasebuffer__`.
+        unsafe { buffer::fill(&slf, view, flags) }
+    }
+
+    #[cfg(any(not(Py_LIMITED_API), Py_3_11))]
+    unsafe fn __releasebuffer__(&self, view: *mut pyo3::ffi::Py_buffer) {
+        // SAFETY: Python releases each view filled by `__getbuffer__` once.
+        unsafe { buffer::release(view) }
+    }
+
+    /// Returns the `(device_type, device_id)` pair of the tensor.
+    fn __dlpack_device__(&self) -> (u32, i32) {
+        let device = self.local().device();
+        (device.device_type.0, device.device_id)
+    }
+
+    /// The tensor shape.
+    #[getter]
+    fn shape<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        PyTuple::new(py, self.local().shape()?)
+    }
+
+    /// The element type, such as `"float32"`.
+    #[getter]
+    fn dtype(&self) -> String {
+        self.local().dtype().to_string()
+    }
+
+    /// The `(device_type, device_id)` pair, as returned by `__dlpack_device__`.
+    #[getter]
+    fn device(&self) -> (u32, i32) {
+        self.__dlpack_device__()
+    }
+
+    fn __repr__(&self) -> PyResult<String> {
+        Ok(format!(
+            "DlpackTensor(shape={:?}, dtype={}, device={})",
+            self.local().shape()?,
+            self.local().dtype(),
+            self.local().device()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        allocation::fixed::make_test_tensor,
+        ffi::{DLDataType, DLDeviceType},
+    };
+    use pyo3::{
+        Py,
+        conversion::FromPyObject,
+        types::{PyAnyMethods, PyModule},
+    };
+    use std::ffi::c_void;
+
+    fn tensor(flags: DlpackFlags) -> PyDlpackTensor {
+        tensor_on(DLDevice::CPU, [2, 3], [3, 1], flags)
+    }
+
+    fn tensor_on(
+        device: DLDevice,
+        shape: [i64; 2],
+        strides: [i64; 2],
+        flags: DlpackFlags,
+    ) -> PyDlpackTensor {
+        let data = Box::new(vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let data_ptr = data.as_ptr() as *mut c_void;
+        make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            data,
+            data_ptr,
+            DLDataType::of::<f32>(),
+            device,
+            shape,
+            strides,
+            flags,
+        )
+        .into()
+    }
+
+    #[test]
+    fn exports_views_through_the_consumer_protocol() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let object = Py::new(py, tensor(DlpackFlags::IS_COPIED))?
+                .into_bound(py)
+                .into_any();
+            assert_eq!(object.getattr("shape")?.extract::<(i64, i64)>()?, (2, 3));
+            assert_eq!(object.getattr("dtype")?.extract::<String>()?, "float32");
+            assert_eq!(object.getattr("device")?.extract::<(u32, i32)>()?, (1, 0));
+
+            let first = Foreign::<DLManagedTensorVersioned>::extract(object.as_borrowed())?;
+            let second = Foreign::<DLManagedTensor>::extract(object.as_borrowed())?;
+            drop(object);
+            assert_eq!(first.flags(), DlpackFlags::empty());
+            assert_eq!(
+                unsafe { first.tensor().cpu_slice::<f32>() }.unwrap(),
+                unsafe { second.tensor().cpu_slice::<f32>() }.unwrap()
+            );
+            assert_eq!(unsafe { second.shape() }.unwrap(), &[2, 3]);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn negotiates_arguments_like_the_array_api() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let module = PyModule::from_code(
+                py,
+                cr#"def export(tensor, **kwargs):
+    try:
+        return type(tensor.__dlpack__(**kwargs)).__name__
+    except BufferError:
+        return "BufferError"
+"#,
+                c"negotiate.py",
+                c"negotiate",
+            )?;
+            let export = module.getattr("export")?;
+            let call = |tensor: &Bound<'_, PyAny>, kwargs: &str| -> PyResult<String> {
+                let kwargs = py.eval(&std::ffi::CString::new(kwargs).unwrap(), None, None)?;
+                export
+                    .call((tensor,), Some(&kwargs.cast_into()?))?
+                    .extract()
+            };
+
+            let writable = Py::new(py, tensor(DlpackFlags::empty()))?.into_bound(py);
+            assert_eq!(call(&writable, "{'stream': None}")?, "PyCapsule");
+            assert_eq!(
+                call(&writable, "{'dl_device': (1, 0), 'copy': False}")?,
+                "PyCapsule"
+            );
+            assert_eq!(call(&writable, "{'dl_device': (2, 0)}")?, "BufferError");
+            assert_eq!(call(&writable, "{'copy': True}")?, "PyCapsule");
+
+            let read_only = Py::new(py, tensor(DlpackFlags::READ_ONLY))?.into_bound(py);
+            assert_eq!(call(&read_only, "{'max_version': (1, 0)}")?, "PyCapsule");
+            assert_eq!(call(&read_only, "{'max_version': (0, 8)}")?, "BufferError");
+            assert_eq!(call(&read_only, "{}")?, "BufferError");
+            assert_eq!(call(&read_only, "{'copy': True}")?, "PyCapsule");
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn copies_and_host_views_follow_the_request() {
+        pyo3::Python::initialize();
+        pyo3::Python::attach(|py| -> pyo3::PyResult<()> {
+            let export = |tensor: &PyDlpackTensor,
+                          dl_device: Option<(u32, i32)>,
+                          copy: Option<bool>|
+             -> PyResult<Foreign<DLManagedTensorVersioned>> {
+                let capsule = tensor.__dlpack__(py, None, Some((1, 0)), dl_device, copy)?;
+                Foreign::extract(capsule.as_borrowed())
+            };
+            let message = |result: PyResult<Foreign<DLManagedTensorVersioned>>| {
+                result.err().unwrap().value(py).to_string()
+            };
+
+            let transposed = tensor_on(DLDevice::CPU, [3, 2], [1, 3], DlpackFlags::READ_ONLY);
+            let copied = export(&transposed, None, Some(true))?;
+            assert_eq!(copied.flags(), DlpackFlags::IS_COPIED);
+            assert!(unsafe { copied.is_compact() }.unwrap());
+            assert_eq!(
+                unsafe { copied.cpu_slice::<f32>() }.unwrap(),
+                &[1.0, 4.0, 2.0, 5.0, 3.0, 6.0]
+            );
+            assert_ne!(
+                unsafe { copied.tensor() }.data,
+                transposed.local().tensor().data
+            );
+
+            let pinned_device = DLDevice {
+                device_type: DLDeviceType::CUDAHOST,
+                device_id: 0,
+            };
+            let pinned = tensor_on(pinned_device, [2, 3], [3, 1], DlpackFlags::empty());
+            let view = export(&pinned, Some((1, 0)), Some(false))?;
+            assert_eq!(view.device(), DLDevice::CPU);
+            assert_eq!(unsafe { view.tensor() }.data, pinned.local().tensor().data);
+            assert_eq!(export(&pinned, None, None)?.device(), pinned_device);
+            let copied = export(&pinned, Some((1, 0)), Some(true))?;
+            assert_eq!(copied.device(), DLDevice::CPU);
+            assert_eq!(copied.flags(), DlpackFlags::IS_COPIED);
+            assert_eq!(
+                message(export(&pinned, None, Some(true))),
+                "cannot copy a tensor on cuda_host:0 to cuda_host:0: copies are only made from \
+                 host-accessible memory into CPU memory"
+            );
+
+            let cuda = tensor_on(DLDevice::cuda(1), [2, 3], [3, 1], DlpackFlags::empty());
+            assert_eq!(
+                message(export(&cuda, Some((1, 0)), Some(false))),
+                "copy=False was requested, but exporting a tensor on cuda:1 to cpu:0 needs a copy"
+            );
+            assert_eq!(
+                message(export(&cuda, Some((1, 0)), None)),
+                "cannot export a tensor on cuda:1 to cpu:0"
+            );
+            assert!(export(&cuda, Some((2, 1)), Some(false)).is_ok());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,81 @@
+//! Producer-side handling of the array API `copy` and `dl_device` requests.
+//!
+//! A `DlpackTensor` can hand out a view of its data on its own device, or on
+//! the CPU when the data is host accessible, and a deep copy of host
+//! accessible data. Anything else raises `BufferError`, as the array API
+//! requires when a request cannot be honoured.
+
+use crate::{
+    AlignedBuffer, DlpackFlags, HostAccess, Local, ManagedTensorBase,
+    ffi::{DLDevice, DLDeviceType, DLTensor},
+    metadata::{Copied, Dynamic},
+    tensor,
+};
+use pyo3::{PyResult, exceptions::PyBufferError};
+
+/// How an export satisfies the consumer's requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Export {
+    /// A zero-copy view labelled with the given device.
+    View(DLDevice),
+    /// A compact CPU copy owned by the export.
+    Copy,
+}
+
+/// Decides how to export a tensor on `device` for a consumer passing
+/// `dl_device` and `copy` to `__dlpack__`.
+pub(super) fn negotiate(
+    device: DLDevice,
+    dl_device: Option<(u32, i32)>,
+    copy: Option<bool>,
+) -> PyResult<Export> {
+    let target = dl_device.map_or(device, |(device_type, device_id)| DLDevice {
+        device_type: DLDeviceType(device_type),
+        device_id,
+    });
+    let host = HostAccess::all().allows(device.device_type);
+    // Host-accessible memory is already usable on the CPU, so it can be
+    // viewed there without a copy.
+    let viewable = target == device || (target == DLDevice::CPU && host);
+    match copy {
+        Some(true) if host && target == DLDevice::CPU => Ok(Export::Copy),
+        Some(true) => Err(PyBufferError::new_err(format!(
+            "cannot copy a tensor on {device} to {target}: copies are only made from \
+             host-accessible memory into CPU memory"
+        ))),
+        _ if viewable => Ok(Export::View(target)),
+        Some(false) => Err(PyBufferError::new_err(format!(
+            "copy=False was requested, but exporting a tensor on {device} to {target} needs a copy"
+        ))),
+        None => Err(PyBufferError::new_err(format!(
+            "cannot export a tensor on {device} to {target}"
+        ))),
+    }
+}
+
+/// Copies the elements of `source`, which must be host accessible, into a
+/// compact, owned CPU tensor marked [`DlpackFlags::IS_COPIED`].
+///
+/// # Safety
+///
+/// `source` must be a valid descriptor whose elements are readable from the
+/// host.
+pub(super) unsafe fn deep_copy<M: ManagedTensorBase>(source: &DLTensor) -> PyResult<Local<M>> {
+    let shape = unsafe { source.shape() }?;
+    let strides = tensor::compact_strides(shape)?;
+    let mut data = AlignedBuffer::<u8>::zeroed(unsafe { source.num_bytes() }?)?;
+    unsafe { source.gather_bytes_into(HostAccess::all(), &mut data) }?;
+    let data_ptr = if data.is_empty() {
+        std::ptr::null_mut()
+    } else {
+        data.as_mut_ptr().cast()
+    };
+    let prepared = Dynamic::new(Copied(shape), Copied(&strides[..])).prepare::<M>()?;
+    let mut initialized = prepared.initialize(Box::new(data))?;
+    initialized.set_data(data_ptr);
+    initialized.set_dtype(source.dtype);
+    initialized.set_device(DLDevice::CPU);
+    initialized.set_flags_unchecked(DlpackFlags::IS_COPIED);
+    // SAFETY: the compact descriptor addresses exactly the owned buffer.
+    Ok(unsafe { initialized.finish() })
+}
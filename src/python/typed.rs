@@ -0,0 +1,261 @@
+//! Validated tensor arguments for `#[pyfunction]`s.
+//!
+//! Extracting one of these types imports the argument through DLPack, with
+//! the same version negotiation as [`AnyForeign`], and checks device, dtype,
+//! rank and contiguity once. Problems raise `TypeError` for the dtype or
+//! rank and `BufferError` for the device or layout, and PyO3 adds a note
+//! naming the argument. The data is then available through safe accessors
+//! for as long as the argument lives.
+
+use crate::{
+    AnyForeign, DlpackElement, Foreign,
+    ffi::{DLDeviceType, DLManagedTensorVersioned},
+};
+use pyo3::{
+    Borrowed, PyAny, PyErr, PyResult,
+    conversion::FromPyObject,
+    exceptions::{PyBufferError, PyTypeError},
+};
+
+/// A compact CPU tensor of `T` with `NDIM` dimensions.
+pub struct PyTensorRef<T, const NDIM: usize> {
+    data: Validated<T>,
+    shape: [usize; NDIM],
+}
+
+/// A compact CPU tensor of `T` of any rank.
+pub struct PyTensorDyn<T> {
+    data: Validated<T>,
+    shape: Vec<usize>,
+}
+
+/// An imported tensor whose data was validated as a slice of `T`.
+struct Validated<T> {
+    tensor: Foreign<DLManagedTensorVersioned>,
+    ptr: *const T,
+    len: usize,
+}
+
+impl<T: DlpackElement> Validated<T> {
+    fn extract(ob: Borrowed<'_, '_, PyAny>, ndim: Option<usize>) -> PyResult<(Self, Vec<usize>)> {
+        let tensor = AnyForeign::extract(ob)?.into_versioned();
+        let device = tensor.device();
+        if device.device_type != DLDeviceType::CPU {
+            return Err(PyBufferError::new_err(format!(
+                "expected a CPU tensor, got one on {device}"
+            )));
+        }
+        let dtype = tensor.dtype();
+        if !dtype.is::<T>() {
+            return Err(PyTypeError::new_err(format!(
+                "expected a {} tensor, got {dtype}",
+                T::DTYPE
+            )));
+        }
+        // SAFETY: the producer handed over a tensor following the DLPack
+        // contract, so its metadata and data are readable until it is
+        // released.
+        let shape = unsafe { tensor.shape() }?;
+        if let Some(ndim) = ndim
+            && shape.len() != ndim
+        {
+            return Err(PyTypeError::new_err(format!(
+                "expected a {ndim}-dimensional tensor, got shape {shape:?}"
+            )));
+        }
+        if !unsafe { tensor.is_compact() }? {
+            let strides = unsafe { tensor.strides_or_compact() }?;
+            return Err(PyBufferError::new_err(format!(
+                "expected a C-contiguous tensor, got strides {strides:?} for shape {shape:?}"
+            )));
+        }
+        let slice = unsafe { tensor.cpu_slice::<T>() }?;
+        let (ptr, len) = (slice.as_ptr(), slice.len());
+        // `cpu_slice` rejects negative dimensions.
+        let shape = shape.iter().map(|&dim| dim as usize).collect();
+        Ok((Self { tensor, ptr, len }, shape))
+    }
+
+    fn as_slice(&self) -> &[T] {
+        // SAFETY: validated at extraction; `tensor` keeps the data alive.
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl<'py, T: DlpackElement, const NDIM: usize> FromPyObject<'_, 'py> for PyTensorRef<T, NDIM> {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'_, 'py, PyAny>) -> Result<Self, Self::Error> {
+        let (data, shape) = Validated::extract(ob, Some(NDIM))?;
+        let shape = shape
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("rank was validated"));
+        Ok(Self { data, shape })
+    }
+}
+
+impl<'py, T: DlpackElement> FromPyObject<'_, 'py> for PyTensorDyn<T> {
+    type Error = PyErr;
+
+    fn extract(ob: Borrowed<'_, 'py, PyAny>) -> Result<Self, Self::Error> {
+        let (data, shape) = Validated::extract(ob, None)?;
+        Ok(Self { data, shape })
+    }
+}
+
+impl<T: DlpackElement, const NDIM: usize> PyTensorRef<T, NDIM> {
+    /// Returns the shape.
+    pub fn shape(&self) -> [usize; NDIM] {
+        self.shape
+    }
+
+    /// Returns the elements in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Returns an ndarray view of the elements.
+    #[cfg(feature = "ndarray")]
+    pub fn as_array(&self) -> ndarray::ArrayView<'_, T, ndarray::Dim<[usize; NDIM]>>
+    where
+        ndarray::Dim<[usize; NDIM]>: ndarray::Dimension,
+    {
+        ndarray::ArrayViewD::from_shape(&self.shape[..], self.as_slice())
+            .and_then(|view| view.into_dimensionality())
+            .unwrap_or_else(|_| unreachable!("shape was validated"))
+    }
+
+    /// Returns the imported tensor.
+    pub fn into_foreign(self) -> Foreign<DLManagedTensorVersioned> {
+        self.data.tensor
+    }
+}
+
+impl<T: DlpackElement> PyTensorDyn<T> {
+    /// Returns the shape.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the elements in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Returns an ndarray view of the elements.
+    #[cfg(feature = "ndarray")]
+    pub fn as_array(&self) -> ndarray::ArrayViewD<'_, T> {
+        ndarray::ArrayViewD::from_shape(self.shape.as_slice(), self.as_slice())
+            .unwrap_or_else(|_| unreachable!("shape was validated"))
+    }
+
+    /// Returns the imported tensor.
+    pub fn into_foreign(self) -> Foreign<DLManagedTensorVersioned> {
+        self.data.tensor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        DlpackFlags,
+        allocation::fixed::make_test_tensor,
+        ffi::{DLDataType, DLDevice},
+        python::PyDlpackTensor,
+    };
+    use pyo3::{
+        Bound, Py, Python, prelude::PyAnyMethods, pyfunction, types::PyCFunction, wrap_pyfunction,
+    };
+    use std::ffi::c_void;
+
+    #[pyfunction]
+    fn total(matrix: PyTensorRef<f32, 2>) -> f32 {
+        matrix.as_slice().iter().sum()
+    }
+
+    fn tensor<'py>(py: Python<'py>, dtype: DLDataType, strides: [i64; 2]) -> Bound<'py, PyAny> {
+        let data = Box::new(vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let data_ptr = data.as_ptr() as *mut c_void;
+        let tensor = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            data,
+            data_ptr,
+            dtype,
+            DLDevice::CPU,
+            [2, 3],
+            strides,
+            DlpackFlags::empty(),
+        );
+        Py::new(py, PyDlpackTensor::new(tensor))
+            .unwrap()
+            .into_bound(py)
+            .into_any()
+    }
+
+    #[test]
+    fn validated_arguments_expose_safe_views() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let matrix = tensor(py, DLDataType::of::<f32>(), [3, 1]);
+            let typed = PyTensorRef::<f32, 2>::extract(matrix.as_borrowed())?;
+            assert_eq!(typed.shape(), [2, 3]);
+            assert_eq!(typed.as_slice(), &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+            #[cfg(feature = "ndarray")]
+            assert_eq!(typed.as_array()[[1, 2]], 6.0);
+
+            let dynamic = PyTensorDyn::<f32>::extract(matrix.as_borrowed())?;
+            assert_eq!(dynamic.shape(), &[2, 3]);
+            #[cfg(feature = "ndarray")]
+            assert_eq!(dynamic.as_array()[[0, 1]], 2.0);
+
+            let total: Bound<'_, PyCFunction> = wrap_pyfunction!(total, py)?;
+            assert_eq!(total.call1((matrix,))?.extract::<f32>()?, 21.0);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn invalid_arguments_raise_precise_errors() {
+        Python::initialize();
+        Python::attach(|py| -> PyResult<()> {
+            let total = wrap_pyfunction!(total, py)?;
+            let message = |err: PyErr| err.value(py).to_string();
+
+            let err = total
+                .call1((tensor(py, DLDataType::of::<i32>(), [3, 1]),))
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                err.value(py)
+                    .getattr("__notes__")?
+                    .extract::<Vec<String>>()?,
+                ["while processing 'matrix'"]
+            );
+            assert_eq!(message(err), "expected a float32 tensor, got int32");
+
+            let err = total
+                .call1((tensor(py, DLDataType::of::<f32>(), [1, 2]),))
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyBufferError>(py));
+            assert_eq!(
+                message(err),
+                "expected a C-contiguous tensor, got strides [1, 2] for shape [2, 3]"
+            );
+
+            let vector = tensor(py, DLDataType::of::<f32>(), [3, 1]);
+            let err = PyTensorRef::<f32, 1>::extract(vector.as_borrowed())
+                .err()
+                .unwrap();
+            assert!(err.is_instance_of::<PyTypeError>(py));
+            assert_eq!(
+                message(err),
+                "expected a 1-dimensional tensor, got shape [2, 3]"
+            );
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,344 @@
+//! Loading and saving [safetensors] files.
+//!
+//! A loaded file is mapped once; every tensor points into that mapping and
+//! holds an [`Arc`] of it as its manager context, so the file stays mapped
+//! until the last tensor is dropped. The tensors are read-only, which only
+//! the versioned ABI can express.
+//!
+//! [safetensors]: https://github.com/huggingface/safetensors
+
+use crate::{
+    DlpackFlags, Foreign, Local, ManagedTensorBase,
+    ffi::{DLDataType, DLDevice, DLManagedTensorVersioned, DLTensor},
+    metadata::{self, Copied, Dynamic},
+    tensor,
+};
+use ::safetensors::{Dtype, SafeTensorError, SafeTensors, View};
+use memmap2::Mmap;
+use snafu::{ResultExt, Snafu};
+use std::{borrow::Cow, fs::File, io::Write, sync::Arc};
+
+/// Length of the little-endian header size that starts every file.
+const HEADER_LEN_SIZE: usize = 8;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(transparent)]
+    Tensor { source: tensor::Error },
+
+    #[snafu(transparent)]
+    Metadata { source: metadata::Error },
+
+    #[snafu(display("safetensors I/O failed: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("invalid safetensors data: {source}"))]
+    Format { source: SafeTensorError },
+
+    #[snafu(display("tensor {name:?} has unsupported safetensors dtype {dtype:?}"))]
+    UnsupportedDtype { name: String, dtype: Dtype },
+
+    #[snafu(display("tensor {name:?} has dtype {dtype} with no safetensors equivalent"))]
+    UnrepresentableDtype { name: String, dtype: DLDataType },
+}
+
+/// Maps a safetensors file and returns its tensors in file order.
+///
+/// Every tensor is a compact, read-only CPU tensor pointing into one shared
+/// mapping. The header and offsets are validated before any tensor is
+/// created.
+///
+/// # Safety
+///
+/// The file must not be modified or truncated while any of the tensors is
+/// alive.
+pub unsafe fn load_safetensors(
+    file: &File,
+) -> Result<Vec<(String, Local<DLManagedTensorVersioned>)>, Error> {
+    let mmap = Arc::new(unsafe { Mmap::map(file) }.context(IoSnafu)?);
+    let (header_len, metadata) = SafeTensors::read_metadata(&mmap).context(FormatSnafu)?;
+    let data_start = HEADER_LEN_SIZE + header_len;
+
+    metadata
+        .offset_keys()
+        .into_iter()
+        .map(|name| {
+            let info = metadata
+                .info(&name)
+                .expect("offset keys name tensors in the header");
+            let dtype = dl_dtype(info.dtype).ok_or_else(|| Error::UnsupportedDtype {
+                name: name.clone(),
+                dtype: info.dtype,
+            })?;
+            let shape = info
+                .shape
+                .iter()
+                .map(|&dim| i64::try_from(dim).map_err(|_| metadata::Error::SpanOverflow))
+                .collect::<Result<Vec<_>, _>>()?;
+            let data = if info.data_offsets.0 == info.data_offsets.1 {
+                std::ptr::null_mut()
+            } else {
+                // SAFETY: `read_metadata` checked the offsets against the
+                // length of the mapping.
+                unsafe { mmap.as_ptr().add(data_start + info.data_offsets.0) }
+                    .cast_mut()
+                    .cast()
+            };
+
+            let strides = tensor::compact_strides(&shape)?;
+            let prepared = Dynamic::new(Copied(&shape[..]), Copied(strides))
+                .prepare::<DLManagedTensorVersioned>()?;
+            let mut initialized = prepared
+                .initialize(Arc::clone(&mmap))
+                .map_err(metadata::Error::from)?;
+            initialized.set_data(data);
+            initialized.set_dtype(dtype);
+            initialized.set_device(DLDevice::CPU);
+            initialized.set_flags_unchecked(DlpackFlags::READ_ONLY);
+            // SAFETY: the descriptor addresses exactly the tensor's bytes
+            // within the mapping, which the context keeps alive.
+            Ok((name, unsafe { initialized.finish() }))
+        })
+        .collect()
+}
+
+/// Writes named CPU tensors as a safetensors file.
+///
+/// Any layout is accepted; non-compact tensors, including those with
+/// negative strides, are gathered in row-major order. The file is assembled
+/// in memory before it is written.
+pub fn write_safetensors<'a, M, W, I>(writer: W, tensors: I) -> Result<(), Error>
+where
+    M: ManagedTensorBase + 'a,
+    W: Write,
+    I: IntoIterator<Item = (&'a str, &'a Local<M>)>,
+{
+    let tensors = tensors
+        .into_iter()
+        .map(|(name, tensor)| (name, tensor.tensor()));
+    // SAFETY: `Local` descriptors and data are trusted.
+    unsafe { write_tensors(writer, tensors) }
+}
+
+/// Writes named foreign CPU tensors as a safetensors file. See
+/// [`write_safetensors`].
+///
+/// # Safety
+///
+/// Every tensor's metadata must be readable and every element its shape and
+/// strides address must be readable host memory.
+pub unsafe fn write_foreign_safetensors<'a, M, W, I>(writer: W, tensors: I) -> Result<(), Error>
+where
+    M: ManagedTensorBase + 'a,
+    W: Write,
+    I: IntoIterator<Item = (&'a str, &'a Foreign<M>)>,
+{
+    let tensors = tensors
+        .into_iter()
+        .map(|(name, tensor)| (name, unsafe { tensor.tensor() }));
+    unsafe { write_tensors(writer, tensors) }
+}
+
+/// Row-major bytes of one tensor, as the safetensors serializer wants them.
+struct Packed<'a> {
+    dtype: Dtype,
+    shape: Vec<usize>,
+    data: Cow<'a, [u8]>,
+}
+
+impl View for &Packed<'_> {
+    fn dtype(&self) -> Dtype {
+        self.dtype
+    }
+
+    fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    fn data(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.data)
+    }
+
+    fn data_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+unsafe fn write_tensors<'a, W: Write>(
+    mut writer: W,
+    tensors: impl Iterator<Item = (&'a str, &'a DLTensor)>,
+) -> Result<(), Error> {
+    let mut packed = Vec::new();
+    for (name, tensor) in tensors {
+        let dtype = st_dtype(tensor.dtype).ok_or_else(|| Error::UnrepresentableDtype {
+            name: name.to_owned(),
+            dtype: tensor.dtype,
+        })?;
+        let shape = unsafe { tensor.shape() }?
+            .iter()
+            .map(|&dim| dim as usize)
+            .collect();
+        let data = if unsafe { tensor.is_compact() }? {
+            Cow::Borrowed(unsafe { tensor.cpu_bytes() }?)
+        } else {
+            Cow::Owned(unsafe { tensor.gather_bytes() }?)
+        };
+        packed.push((name, Packed { dtype, shape, data }));
+    }
+
+    let views = packed.iter().map(|(name, tensor)| (*name, tensor));
+    let bytes = ::safetensors::serialize(views, None).context(FormatSnafu)?;
+    writer.write_all(&bytes).context(IoSnafu)?;
+    writer.flush().context(IoSnafu)
+}
+
+/// Safetensors dtypes and their DLPack equivalents. `F8_E4M3` is the
+/// finite-only E4M3 format PyTorch calls `float8_e4m3fn`.
+const DTYPES: [(Dtype, DLDataType); 22] = [
+    (Dtype::BOOL, DLDataType::BOOL),
+    (Dtype::F4, DLDataType::F4E2M1FN),
+    (Dtype::F6_E2M3, DLDataType::F6E2M3FN),
+    (Dtype::F6_E3M2, DLDataType::F6E3M2FN),
+    (Dtype::U8, DLDataType::U8),
+    (Dtype::I8, DLDataType::I8),
+    (Dtype::F8_E5M2, DLDataType::F8E5M2),
+    (Dtype::F8_E4M3, DLDataType::F8E4M3FN),
+    (Dtype::F8_E8M0, DLDataType::F8E8M0FNU),
+    (Dtype::F8_E4M3FNUZ, DLDataType::F8E4M3FNUZ),
+    (Dtype::F8_E5M2FNUZ, DLDataType::F8E5M2FNUZ),
+    (Dtype::I16, DLDataType::I16),
+    (Dtype::U16, DLDataType::U16),
+    (Dtype::F16, DLDataType::F16),
+    (Dtype::BF16, DLDataType::BF16),
+    (Dtype::I32, DLDataType::I32),
+    (Dtype::U32, DLDataType::U32),
+    (Dtype::F32, DLDataType::F32),
+    (Dtype::C64, DLDataType::C64),
+    (Dtype::F64, DLDataType::F64),
+    (Dtype::I64, DLDataType::I64),
+    (Dtype::U64, DLDataType::U64),
+];
+
+fn dl_dtype(dtype: Dtype) -> Option<DLDataType> {
+    DTYPES
+        .iter()
+        .find(|(st, _)| *st == dtype)
+        .map(|&(_, dl)| dl)
+}
+
+fn st_dtype(dtype: DLDataType) -> Option<Dtype> {
+    DTYPES
+        .iter()
+        .find(|(_, dl)| *dl == dtype)
+        .map(|&(st, _)| st)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::allocation::fixed::make_test_tensor;
+    use std::io::{Seek, SeekFrom};
+
+    fn round_trip(bytes: &[u8]) -> Vec<(String, Local<DLManagedTensorVersioned>)> {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(bytes).unwrap();
+        file.seek(SeekFrom::Start(0)).unwrap();
+        unsafe { load_safetensors(&file) }.unwrap()
+    }
+
+    #[test]
+    fn tensors_share_one_read_only_mapping() {
+        let weight = Local::<DLManagedTensorVersioned>::arange(0.0_f32, 6.0, 1.0).unwrap();
+        let bias = Local::<DLManagedTensorVersioned>::full(&[2], 7_i64).unwrap();
+        let mut bytes = Vec::new();
+        write_safetensors(&mut bytes, [("weight", &weight), ("bias", &bias)]).unwrap();
+
+        let mut tensors = round_trip(&bytes);
+        let (name, weight) = tensors.pop().unwrap();
+        assert_eq!(name, "weight");
+        assert_eq!(weight.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(
+            weight.cpu_slice::<f32>().unwrap(),
+            &[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+
+        let (name, bias) = tensors.pop().unwrap();
+        assert_eq!(name, "bias");
+        let mapping = |tensor: &Local<DLManagedTensorVersioned>| {
+            let managed: &DLManagedTensorVersioned = tensor;
+            managed.manager_ctx
+        };
+        assert_eq!(mapping(&bias), mapping(&weight));
+        drop(weight);
+        assert_eq!(bias.cpu_slice::<i64>().unwrap(), &[7, 7]);
+    }
+
+    #[test]
+    fn strided_tensors_are_gathered_and_ordered_by_offset() {
+        // A transposed 2x3 view of a row-major 3x2 buffer.
+        let storage = [1_u16, 4, 2, 5, 3, 6];
+        let transposed = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(()),
+            storage.as_ptr().cast_mut().cast(),
+            DLDataType::U16,
+            DLDevice::CPU,
+            [2, 3],
+            [1, 2],
+            DlpackFlags::empty(),
+        );
+        let fp8 = [0x38_u8, 0x40, 0xB8];
+        let scales = make_test_tensor::<_, DLManagedTensorVersioned, 1>(
+            Box::new(()),
+            fp8.as_ptr().cast_mut().cast(),
+            DLDataType::F8E4M3FN,
+            DLDevice::CPU,
+            [3],
+            [1],
+            DlpackFlags::empty(),
+        );
+        let mut bytes = Vec::new();
+        write_safetensors(&mut bytes, [("t", &transposed), ("scales", &scales)]).unwrap();
+
+        let tensors = round_trip(&bytes);
+        let names: Vec<&str> = tensors.iter().map(|(name, _)| name.as_str()).collect();
+        // The serializer places wider dtypes first.
+        assert_eq!(names, ["t", "scales"]);
+        let (_, t) = &tensors[0];
+        assert_eq!(t.shape().unwrap(), &[2, 3]);
+        assert_eq!(t.cpu_slice::<u16>().unwrap(), &[1, 2, 3, 4, 5, 6]);
+        let (_, scales) = &tensors[1];
+        assert_eq!(scales.dtype(), DLDataType::F8E4M3FN);
+        assert_eq!(unsafe { scales.tensor().cpu_bytes() }.unwrap(), &fp8);
+    }
+
+    #[test]
+    fn corrupt_files_and_unmapped_dtypes_are_rejected() {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(&[0xFF; 16]).unwrap();
+        assert!(matches!(
+            unsafe { load_safetensors(&file) },
+            Err(Error::Format { .. })
+        ));
+
+        let empty = Local::<DLManagedTensorVersioned>::zeros::<f32>(&[0]).unwrap();
+        let mut bytes = Vec::new();
+        write_safetensors(&mut bytes, [("empty", &empty)]).unwrap();
+        let tensors = round_trip(&bytes);
+        assert!(tensors[0].1.tensor().data.is_null());
+
+        let storage = [0_u64; 2];
+        let complex = make_test_tensor::<_, DLManagedTensorVersioned, 1>(
+            Box::new(()),
+            storage.as_ptr().cast_mut().cast(),
+            DLDataType::C128,
+            DLDevice::CPU,
+            [1],
+            [1],
+            DlpackFlags::empty(),
+        );
+        assert!(matches!(
+            write_safetensors(Vec::new(), [("c", &complex)]),
+            Err(Error::UnrepresentableDtype { .. })
+        ));
+    }
+}
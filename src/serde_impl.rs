@@ -0,0 +1,163 @@
+//! `serde` support for the DLPack descriptor types.
+//!
+//! Data types and devices serialize as the readable names produced by their
+//! `Display` impls (`"float32"`, `"cuda:0"`); versions serialize as
+//! `{"major", "minor"}` structs. [`DlpackFlags`](crate::DlpackFlags) uses the
+//! `bitflags` serde format.
+
+use crate::ffi::{DLDataType, DLDevice, DLPackVersion};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+impl Serialize for DLDataType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DLDataType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&name), &"a DLPack data type name")
+        })
+    }
+}
+
+impl Serialize for DLDevice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DLDevice {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Self::from_name(&name).ok_or_else(|| {
+            de::Error::invalid_value(de::Unexpected::Str(&name), &"a DLPack device name")
+        })
+    }
+}
+
+/// Field-for-field mirror of the generated `DLPackVersion`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "DLPackVersion")]
+struct Version {
+    major: u32,
+    minor: u32,
+}
+
+impl Serialize for DLPackVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Version {
+            major: self.major,
+            minor: self.minor,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DLPackVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let Version { major, minor } = Version::deserialize(deserializer)?;
+        Ok(Self { major, minor })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DlpackFlags, TensorMeta,
+        allocation::fixed::make_test_tensor,
+        ffi::{DLDataType, DLDevice, DLManagedTensor, DLManagedTensorVersioned, DLPackVersion},
+    };
+    use serde_json::json;
+
+    #[test]
+    fn descriptors_serialize_with_readable_names() {
+        assert_eq!(serde_json::to_value(DLDataType::BF16).unwrap(), "bfloat16");
+        assert_eq!(serde_json::to_value(DLDevice::cuda(1)).unwrap(), "cuda:1");
+        assert_eq!(
+            serde_json::to_value(DLPackVersion { major: 1, minor: 2 }).unwrap(),
+            json!({"major": 1, "minor": 2})
+        );
+        assert_eq!(
+            serde_json::to_value(DlpackFlags::READ_ONLY | DlpackFlags::IS_COPIED).unwrap(),
+            "READ_ONLY | IS_COPIED"
+        );
+    }
+
+    #[test]
+    fn descriptors_deserialize_from_readable_names() {
+        assert_eq!(
+            serde_json::from_value::<DLDataType>(json!("float8_e5m2")).unwrap(),
+            DLDataType::F8E5M2
+        );
+        assert_eq!(
+            serde_json::from_value::<DLDevice>(json!("cpu")).unwrap(),
+            DLDevice::CPU
+        );
+        assert_eq!(
+            serde_json::from_value::<DLPackVersion>(json!({"major": 1, "minor": 0})).unwrap(),
+            DLPackVersion { major: 1, minor: 0 }
+        );
+
+        let error = serde_json::from_value::<DLDataType>(json!("float")).unwrap_err();
+        assert!(error.to_string().contains("a DLPack data type name"));
+        let error = serde_json::from_value::<DLDevice>(json!("gpu:0")).unwrap_err();
+        assert!(error.to_string().contains("a DLPack device name"));
+    }
+
+    #[test]
+    fn versioned_tensor_meta_round_trips_through_json() {
+        let mut data = [0.0_f32; 6];
+        let tensor = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(()),
+            data.as_mut_ptr().cast(),
+            DLDataType::F32,
+            DLDevice::cuda(0),
+            [2, 3],
+            [1, 2],
+            DlpackFlags::READ_ONLY,
+        );
+
+        let meta = tensor.meta().unwrap();
+        let value = serde_json::to_value(&meta).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "shape": [2, 3],
+                "strides": [1, 2],
+                "dtype": "float32",
+                "device": "cuda:0",
+                "byte_offset": 0,
+                "version": {
+                    "major": DLPackVersion::CURRENT.major,
+                    "minor": DLPackVersion::CURRENT.minor,
+                },
+                "flags": "READ_ONLY",
+            })
+        );
+        assert_eq!(serde_json::from_value::<TensorMeta>(value).unwrap(), meta);
+
+        let foreign = tensor.into_foreign();
+        assert_eq!(unsafe { foreign.meta() }.unwrap(), meta);
+    }
+
+    #[test]
+    fn legacy_tensor_meta_has_no_version_or_flags() {
+        let mut data = [0_u8; 4];
+        let tensor = make_test_tensor::<_, DLManagedTensor, 1>(
+            Box::new(()),
+            data.as_mut_ptr().cast(),
+            DLDataType::U8,
+            DLDevice::CPU,
+            [4],
+            [1],
+            DlpackFlags::empty(),
+        );
+
+        let value = serde_json::to_value(tensor.meta().unwrap()).unwrap();
+        assert_eq!(value["version"], json!(null));
+        assert_eq!(value["flags"], "");
+    }
+}
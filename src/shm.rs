@@ -0,0 +1,580 @@
+//! Cross-process tensor sharing through Linux `memfd` shared memory.
+//!
+//! [`export`] copies a CPU tensor into a sealed, anonymous `memfd`
+//! and returns the file descriptor with a small [`ShmDescriptor`]. Another
+//! process that receives both, for example through [`send`] and [`recv`],
+//! maps the region with [`import`] as a read-only
+//! [`Foreign<DLManagedTensorVersioned>`](crate::Foreign) whose deleter unmaps
+//! it.
+//!
+//! The region is sealed against writes and resizing before it is shared, so
+//! the importer can map it without trusting the exporter to leave it alone.
+
+use crate::{
+    Foreign, Local, ManagedTensorBase, VersionError,
+    allocation::{
+        dynamic,
+        mmap::{self, FileRegion},
+    },
+    ffi::{DLDataType, DLDataTypeCode, DLManagedTensorVersioned, DLPackVersion},
+    tensor,
+};
+use snafu::{ResultExt, Snafu};
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::{Read, Write},
+    os::{
+        fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd},
+        unix::net::UnixStream,
+    },
+};
+
+/// Seals that make a region immutable and fixed-size.
+const REQUIRED_SEALS: libc::c_int = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE;
+
+const MAGIC: [u8; 4] = *b"DLSH";
+const FORMAT: u8 = 1;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(transparent)]
+    Tensor { source: tensor::Error },
+
+    #[snafu(transparent)]
+    Mmap { source: mmap::Error },
+
+    #[snafu(transparent)]
+    Version { source: VersionError },
+
+    #[snafu(display("shared memory I/O failed: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("shared memory region is not sealed against writes and resizing"))]
+    NotSealed,
+
+    #[snafu(display("malformed shared tensor descriptor: {reason}"))]
+    Decode { reason: &'static str },
+
+    #[snafu(display("message did not carry a file descriptor"))]
+    MissingFd,
+
+    #[snafu(display("message carried more than one file descriptor"))]
+    ExtraFds,
+
+    #[snafu(display("descriptor describes {len} bytes but the region holds {region_len}"))]
+    LengthMismatch { len: u64, region_len: u64 },
+
+    #[snafu(display("tensor reaches {extent} bytes into a region of {len} bytes"))]
+    ExtentOutOfBounds { extent: u64, len: u64 },
+}
+
+/// Everything a receiving process needs, besides the file descriptor itself,
+/// to map a shared tensor.
+///
+/// It encodes to a compact binary form with [`Self::to_bytes`] and, with the
+/// `serde` feature, serializes like other descriptor types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShmDescriptor {
+    /// The descriptor number in the process that produced this value.
+    /// File descriptors are per-process, so a receiver uses the one it was
+    /// handed instead.
+    pub fd: RawFd,
+    pub dtype: DLDataType,
+    pub shape: Vec<i64>,
+    /// Strides in elements, or `None` for compact row-major.
+    pub strides: Option<Vec<i64>>,
+    /// Byte offset of the first element within the region.
+    pub offset: u64,
+    /// Length of the region in bytes.
+    pub len: u64,
+    pub version: DLPackVersion,
+}
+
+/// A tensor copied into shared memory, ready to be handed to another process.
+#[derive(Debug)]
+pub struct ShmExport {
+    pub fd: OwnedFd,
+    pub descriptor: ShmDescriptor,
+}
+
+/// Copies a CPU tensor into a new sealed `memfd` region.
+///
+/// Non-compact tensors, including ones with negative strides, are gathered
+/// into row-major order, so the descriptor always has compact strides.
+pub fn export<M: ManagedTensorBase>(tensor: &Local<M>) -> Result<ShmExport, Error> {
+    let bytes = if tensor.is_compact()? {
+        Cow::Borrowed(tensor.cpu_bytes()?)
+    } else {
+        // SAFETY: `Local` descriptors and data are trusted.
+        Cow::Owned(unsafe { tensor.tensor().gather_bytes() }?)
+    };
+    let name = c"dlpark-tensor";
+    let raw =
+        unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+    if raw < 0 {
+        return Err(std::io::Error::last_os_error()).context(IoSnafu);
+    }
+    // SAFETY: `memfd_create` returned a fresh descriptor that nothing else owns.
+    let mut file = File::from(unsafe { OwnedFd::from_raw_fd(raw) });
+    file.write_all(&bytes).context(IoSnafu)?;
+    if unsafe {
+        libc::fcntl(
+            file.as_raw_fd(),
+            libc::F_ADD_SEALS,
+            REQUIRED_SEALS | libc::F_SEAL_SEAL,
+        )
+    } < 0
+    {
+        return Err(std::io::Error::last_os_error()).context(IoSnafu);
+    }
+
+    let managed: &M = tensor;
+    let descriptor = ShmDescriptor {
+        fd: file.as_raw_fd(),
+        dtype: tensor.dtype(),
+        shape: tensor.shape()?.to_vec(),
+        strides: None,
+        offset: 0,
+        len: bytes.len() as u64,
+        version: managed.version().unwrap_or(DLPackVersion::CURRENT),
+    };
+    Ok(ShmExport {
+        fd: file.into(),
+        descriptor,
+    })
+}
+
+/// Maps a region received from another process as a read-only tensor.
+///
+/// `fd` must be sealed against writes and resizing, as regions from
+/// [`export`] are. The region must be exactly `descriptor.len` bytes long,
+/// and the tensor's offset and byte extent must fit within it. The
+/// mapping is released when the returned tensor is dropped. The result is a
+/// [`Foreign`] because its contents come from another process.
+pub fn import(
+    fd: OwnedFd,
+    descriptor: &ShmDescriptor,
+) -> Result<Foreign<DLManagedTensorVersioned>, Error> {
+    crate::version::validate_version(descriptor.version)?;
+    let seals = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GET_SEALS) };
+    if seals < 0 || seals & REQUIRED_SEALS != REQUIRED_SEALS {
+        return Err(Error::NotSealed);
+    }
+
+    let region = FileRegion {
+        dtype: descriptor.dtype,
+        shape: descriptor.shape.clone(),
+        strides: descriptor.strides.clone(),
+        offset: descriptor.offset,
+    };
+    let file = File::from(fd);
+    let region_len = file.metadata().context(IoSnafu)?.len();
+    if region_len != descriptor.len {
+        return Err(Error::LengthMismatch {
+            len: descriptor.len,
+            region_len,
+        });
+    }
+    let (_, bytes) = region.layout().map_err(mmap::Error::from)?;
+    let extent = descriptor.offset.saturating_add(bytes);
+    if extent > descriptor.len {
+        return Err(Error::ExtentOutOfBounds {
+            extent,
+            len: descriptor.len,
+        });
+    }
+    // SAFETY: the seals checked above forbid any process from modifying or
+    // truncating the region.
+    let initialized = unsafe { dynamic::Initialized::from_mmap(&file, &region) }?;
+    // SAFETY: `from_mmap` validated the layout against the region size.
+    Ok(unsafe { initialized.finish() }.into_foreign())
+}
+
+impl ShmDescriptor {
+    /// Encodes the descriptor in a little-endian binary form.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(48 + 16 * self.shape.len());
+        out.extend_from_slice(&MAGIC);
+        out.push(FORMAT);
+        out.extend_from_slice(&self.version.major.to_le_bytes());
+        out.extend_from_slice(&self.version.minor.to_le_bytes());
+        out.push(self.dtype.code.0);
+        out.push(self.dtype.bits);
+        out.extend_from_slice(&self.dtype.lanes.to_le_bytes());
+        out.extend_from_slice(&self.fd.to_le_bytes());
+        out.extend_from_slice(&self.offset.to_le_bytes());
+        out.extend_from_slice(&self.len.to_le_bytes());
+        out.extend_from_slice(&(self.shape.len() as u32).to_le_bytes());
+        for dim in &self.shape {
+            out.extend_from_slice(&dim.to_le_bytes());
+        }
+        out.push(u8::from(self.strides.is_some()));
+        for stride in self.strides.iter().flatten() {
+            out.extend_from_slice(&stride.to_le_bytes());
+        }
+        out
+    }
+
+    /// Decodes a descriptor produced by [`Self::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        if reader.take::<4>()? != MAGIC {
+            return Err(Error::Decode {
+                reason: "bad magic",
+            });
+        }
+        if reader.take::<1>()?[0] != FORMAT {
+            return Err(Error::Decode {
+                reason: "unsupported format",
+            });
+        }
+        let version = DLPackVersion {
+            major: u32::from_le_bytes(reader.take()?),
+            minor: u32::from_le_bytes(reader.take()?),
+        };
+        let [code, bits] = reader.take()?;
+        let dtype = DLDataType {
+            code: DLDataTypeCode(code),
+            bits,
+            lanes: u16::from_le_bytes(reader.take()?),
+        };
+        let fd = i32::from_le_bytes(reader.take()?);
+        let offset = u64::from_le_bytes(reader.take()?);
+        let len = u64::from_le_bytes(reader.take()?);
+        let ndim = u32::from_le_bytes(reader.take()?) as usize;
+        if ndim > reader.0.len() / 8 {
+            return Err(Error::Decode {
+                reason: "truncated shape",
+            });
+        }
+        let shape = (0..ndim)
+            .map(|_| reader.take().map(i64::from_le_bytes))
+            .collect::<Result<Vec<_>, _>>()?;
+        let strides = match reader.take::<1>()?[0] {
+            0 => None,
+            1 => Some(
+                (0..ndim)
+                    .map(|_| reader.take().map(i64::from_le_bytes))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+            _ => {
+                return Err(Error::Decode {
+                    reason: "bad strides marker",
+                });
+            }
+        };
+        if !reader.0.is_empty() {
+            return Err(Error::Decode {
+                reason: "trailing bytes",
+            });
+        }
+        Ok(Self {
+            fd,
+            dtype,
+            shape,
+            strides,
+            offset,
+            len,
+            version,
+        })
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (head, rest) = self.0.split_first_chunk::<N>().ok_or(Error::Decode {
+            reason: "truncated",
+        })?;
+        self.0 = rest;
+        Ok(*head)
+    }
+}
+
+/// Sends `descriptor` and `fd` over a Unix stream socket, passing the
+/// descriptor with `SCM_RIGHTS`.
+pub fn send(
+    stream: &UnixStream,
+    fd: BorrowedFd<'_>,
+    descriptor: &ShmDescriptor,
+) -> Result<(), Error> {
+    send_fds(stream, &[fd.as_raw_fd()], descriptor)
+}
+
+fn send_fds(stream: &UnixStream, fds: &[RawFd], descriptor: &ShmDescriptor) -> Result<(), Error> {
+    let payload = descriptor.to_bytes();
+    let mut message = (payload.len() as u32).to_le_bytes().to_vec();
+    message.extend_from_slice(&payload);
+    send_message(stream, fds, &message)
+}
+
+/// Sends the length-prefixed `message`, passing `fds` with its first byte.
+fn send_message(stream: &UnixStream, fds: &[RawFd], message: &[u8]) -> Result<(), Error> {
+    let mut iov = libc::iovec {
+        iov_base: message.as_ptr().cast_mut().cast(),
+        iov_len: message.len(),
+    };
+    let fds_len = size_of_val(fds) as u32;
+    let space = unsafe { libc::CMSG_SPACE(fds_len) } as usize;
+    let mut control = vec![0u8; space];
+    let mut header: libc::msghdr = unsafe { std::mem::zeroed() };
+    header.msg_iov = &mut iov;
+    header.msg_iovlen = 1;
+    header.msg_control = control.as_mut_ptr().cast();
+    header.msg_controllen = space as _;
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&header);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(fds_len) as _;
+        let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+        for (index, &fd) in fds.iter().enumerate() {
+            data.add(index).write_unaligned(fd);
+        }
+    }
+
+    let sent = unsafe { libc::sendmsg(stream.as_raw_fd(), &header, libc::MSG_NOSIGNAL) };
+    if sent < 0 {
+        return Err(std::io::Error::last_os_error()).context(IoSnafu);
+    }
+    // The descriptor travelled with the first byte; the rest is plain data.
+    (&*stream)
+        .write_all(&message[sent as usize..])
+        .context(IoSnafu)
+}
+
+/// Receives a descriptor and file descriptor sent with [`send`].
+///
+/// The returned descriptor's `fd` field is updated to the received number.
+/// A message carrying more than one file descriptor is rejected with
+/// [`Error::ExtraFds`], and all of them are closed.
+pub fn recv(stream: &UnixStream) -> Result<(OwnedFd, ShmDescriptor), Error> {
+    let mut prefix = [0u8; 4];
+    let mut iov = libc::iovec {
+        iov_base: prefix.as_mut_ptr().cast(),
+        iov_len: prefix.len(),
+    };
+    let space = unsafe { libc::CMSG_SPACE(size_of::<RawFd>() as u32) } as usize;
+    let mut control = vec![0u8; space];
+    let mut header: libc::msghdr = unsafe { std::mem::zeroed() };
+    header.msg_iov = &mut iov;
+    header.msg_iovlen = 1;
+    header.msg_control = control.as_mut_ptr().cast();
+    header.msg_controllen = space as _;
+
+    let received =
+        unsafe { libc::recvmsg(stream.as_raw_fd(), &mut header, libc::MSG_CMSG_CLOEXEC) };
+    if received < 0 {
+        return Err(std::io::Error::last_os_error()).context(IoSnafu);
+    }
+    let mut fds = Vec::new();
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&header);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg).cast::<RawFd>();
+                let count =
+                    ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize) / size_of::<RawFd>();
+                for index in 0..count {
+                    // SAFETY: the kernel installed these descriptors for us.
+                    fds.push(OwnedFd::from_raw_fd(data.add(index).read_unaligned()));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&header, cmsg);
+        }
+    }
+    // Extra descriptors are closed when `fds` is dropped; any that did not
+    // fit the control buffer were already closed by the kernel.
+    if fds.len() > 1 || header.msg_flags & libc::MSG_CTRUNC != 0 {
+        return Err(Error::ExtraFds);
+    }
+    let fd = fds.pop().ok_or(Error::MissingFd)?;
+    if received == 0 {
+        return Err(Error::Decode {
+            reason: "truncated",
+        });
+    }
+
+    let mut reader = stream;
+    reader
+        .read_exact(&mut prefix[received as usize..])
+        .context(IoSnafu)?;
+    // The payload grows with what arrives rather than with the prefix, which
+    // the peer controls.
+    let len = u32::from_le_bytes(prefix) as usize;
+    let mut payload = Vec::new();
+    reader
+        .take(len as u64)
+        .read_to_end(&mut payload)
+        .context(IoSnafu)?;
+    if payload.len() != len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)).context(IoSnafu);
+    }
+
+    let mut descriptor = ShmDescriptor::from_bytes(&payload)?;
+    descriptor.fd = fd.as_raw_fd();
+    Ok((fd, descriptor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DlpackFlags;
+    use std::os::fd::AsFd;
+
+    fn sample() -> Local<DLManagedTensorVersioned> {
+        Local::from_vec(vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]).unwrap()
+    }
+
+    #[test]
+    fn exported_region_imports_read_only() {
+        let ShmExport { fd, descriptor } = export(&sample()).unwrap();
+        assert_eq!(descriptor.len, 24);
+        assert_eq!(descriptor.shape, [2, 3]);
+
+        let foreign = import(fd, &descriptor).unwrap();
+        assert_eq!(foreign.flags(), DlpackFlags::READ_ONLY);
+        assert_eq!(unsafe { foreign.shape() }.unwrap(), &[2, 3]);
+        assert_eq!(
+            unsafe { foreign.cpu_slice::<f32>() }.unwrap(),
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn non_compact_tensors_are_exported_in_row_major_order() {
+        // The 2x3 buffer [1..6] viewed transposed as a 3x2 tensor.
+        let data = vec![1_i32, 2, 3, 4, 5, 6];
+        let data_ptr = data.as_ptr() as *mut std::ffi::c_void;
+        let transposed = crate::allocation::fixed::make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(data),
+            data_ptr,
+            DLDataType::I32,
+            crate::ffi::DLDevice::CPU,
+            [3, 2],
+            [1, 3],
+            DlpackFlags::empty(),
+        );
+
+        let ShmExport { fd, descriptor } = export(&transposed).unwrap();
+        assert_eq!(descriptor.strides, None);
+        let foreign = import(fd, &descriptor).unwrap();
+        assert_eq!(
+            unsafe { foreign.cpu_slice::<i32>() }.unwrap(),
+            &[1, 4, 2, 5, 3, 6]
+        );
+    }
+
+    #[test]
+    fn unsealed_or_oversized_regions_are_rejected() {
+        let raw = unsafe { libc::memfd_create(c"plain".as_ptr(), libc::MFD_CLOEXEC) };
+        let mut file = File::from(unsafe { OwnedFd::from_raw_fd(raw) });
+        file.write_all(&[0; 24]).unwrap();
+        let descriptor = export(&sample()).unwrap().descriptor;
+        assert!(matches!(
+            import(file.into(), &descriptor),
+            Err(Error::NotSealed)
+        ));
+
+        let ShmExport { fd, mut descriptor } = export(&sample()).unwrap();
+        descriptor.shape = vec![3, 3];
+        descriptor.len = 24;
+        assert!(matches!(
+            import(fd, &descriptor),
+            Err(Error::ExtentOutOfBounds {
+                extent: 36,
+                len: 24
+            })
+        ));
+
+        let ShmExport { fd, mut descriptor } = export(&sample()).unwrap();
+        descriptor.len = 4096;
+        assert!(matches!(
+            import(fd, &descriptor),
+            Err(Error::LengthMismatch {
+                len: 4096,
+                region_len: 24
+            })
+        ));
+
+        let ShmExport { fd, mut descriptor } = export(&sample()).unwrap();
+        descriptor.offset = 8;
+        assert!(matches!(
+            import(fd, &descriptor),
+            Err(Error::ExtentOutOfBounds {
+                extent: 32,
+                len: 24
+            })
+        ));
+    }
+
+    #[test]
+    fn descriptor_round_trips_through_bytes() {
+        let descriptor = ShmDescriptor {
+            fd: 7,
+            dtype: DLDataType::BF16,
+            shape: vec![4, 5],
+            strides: Some(vec![1, 4]),
+            offset: 512,
+            len: 1024,
+            version: DLPackVersion::CURRENT,
+        };
+        let bytes = descriptor.to_bytes();
+        assert_eq!(ShmDescriptor::from_bytes(&bytes).unwrap(), descriptor);
+        assert!(matches!(
+            ShmDescriptor::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::Decode { .. })
+        ));
+        assert!(matches!(
+            ShmDescriptor::from_bytes(b"nope"),
+            Err(Error::Decode {
+                reason: "bad magic"
+            })
+        ));
+    }
+
+    #[test]
+    fn descriptor_and_fd_travel_over_a_socket() {
+        let (left, right) = UnixStream::pair().unwrap();
+        let export = export(&sample()).unwrap();
+        send(&left, export.fd.as_fd(), &export.descriptor).unwrap();
+        drop(export.fd);
+
+        let (fd, descriptor) = recv(&right).unwrap();
+        assert_eq!(descriptor.fd, fd.as_raw_fd());
+        assert_eq!(descriptor.shape, export.descriptor.shape);
+        let foreign = import(fd, &descriptor).unwrap();
+        assert_eq!(unsafe { foreign.cpu_slice::<f32>() }.unwrap()[5], 6.0);
+    }
+
+    #[test]
+    fn messages_with_extra_fds_are_rejected_and_closed() {
+        let (left, right) = UnixStream::pair().unwrap();
+        let export = export(&sample()).unwrap();
+        let raw = export.fd.as_raw_fd();
+        send_fds(&left, &[raw, raw], &export.descriptor).unwrap();
+        drop(left);
+
+        assert!(matches!(recv(&right), Err(Error::ExtraFds)));
+    }
+
+    #[test]
+    fn oversized_length_prefixes_fail_on_the_missing_payload() {
+        let (left, right) = UnixStream::pair().unwrap();
+        let export = export(&sample()).unwrap();
+        let mut message = u32::MAX.to_le_bytes().to_vec();
+        message.extend_from_slice(&export.descriptor.to_bytes());
+        send_message(&left, &[export.fd.as_raw_fd()], &message).unwrap();
+        drop(left);
+
+        match recv(&right) {
+            Err(Error::Io { source }) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::UnexpectedEof)
+            }
+            other => panic!("expected an unexpected EOF, got {other:?}"),
+        }
+    }
+}
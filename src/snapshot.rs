@@ -0,0 +1,483 @@
+//! A self-describing binary snapshot format for reproducing interop issues.
+//!
+//! [`write_snapshot`] records a CPU tensor's descriptor exactly as received
+//! (shape, strides, `byte_offset`, device, dtype code, version and flags)
+//! followed by its elements in row-major order. [`read_snapshot`] restores a
+//! compact, owned tensor with the same dtype and shape;
+//! [`read_snapshot_with_meta`] also returns the recorded [`TensorMeta`].
+//!
+//! # Format
+//!
+//! All integers are little-endian.
+//!
+//! | Field | Encoding |
+//! | --- | --- |
+//! | magic | `b"DLPKSNAP"` |
+//! | format version | `u32`, currently 1 |
+//! | DLPack version | `u8` presence marker, then `major`, `minor` as `u32` |
+//! | flags | `u64` |
+//! | dtype | `code: u8`, `bits: u8`, `lanes: u16` |
+//! | device | `device_type: u32`, `device_id: i32` |
+//! | byte offset | `u64` |
+//! | shape | `ndim: u32`, then `ndim` × `i64` |
+//! | strides | `u8` presence marker, then `ndim` × `i64` |
+//! | data | `len: u64`, then `len` bytes |
+//! | checksum | CRC-32 (IEEE) of all preceding bytes, as `u32` |
+
+use crate::{
+    DlpackFlags, Foreign, Local, ManagedTensorBase, TensorMeta,
+    allocation::{self, AlignedBuffer},
+    ffi::{DLDataType, DLDataTypeCode, DLDevice, DLDeviceType, DLPackVersion, DLTensor},
+    metadata::{self, Copied, Dynamic},
+    tensor,
+};
+use snafu::{ResultExt, Snafu};
+use std::io::{Read, Write};
+
+const MAGIC: [u8; 8] = *b"DLPKSNAP";
+const FORMAT: u32 = 1;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(transparent)]
+    Tensor { source: tensor::Error },
+
+    #[snafu(transparent)]
+    Metadata { source: metadata::Error },
+
+    #[snafu(display("snapshot I/O failed: {source}"))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("not a DLPack snapshot"))]
+    BadMagic,
+
+    #[snafu(display("unsupported snapshot format version {format}"))]
+    UnsupportedFormat { format: u32 },
+
+    #[snafu(display(
+        "snapshot checksum mismatch: stored {stored:#010x}, computed {computed:#010x}"
+    ))]
+    ChecksumMismatch { stored: u32, computed: u32 },
+
+    #[snafu(display("malformed snapshot: {reason}"))]
+    Malformed { reason: &'static str },
+}
+
+impl From<allocation::Error> for Error {
+    fn from(source: allocation::Error) -> Self {
+        metadata::Error::from(source).into()
+    }
+}
+
+/// Writes a snapshot of a CPU tensor.
+///
+/// Any layout is accepted, including non-compact and negative strides; the
+/// elements are gathered in row-major order. Packed sub-byte dtypes must be
+/// compact.
+pub fn write_snapshot<M, W>(tensor: &Local<M>, writer: W) -> Result<(), Error>
+where
+    M: ManagedTensorBase,
+    W: Write,
+{
+    let managed: &M = tensor;
+    // SAFETY: `Local` descriptors and data are trusted.
+    unsafe { write_tensor(tensor.tensor(), managed.version(), managed.flags(), writer) }
+}
+
+/// Writes a snapshot of a foreign CPU tensor. See [`write_snapshot`].
+///
+/// # Safety
+///
+/// The tensor's metadata must be readable and every element its shape and
+/// strides address must be readable host memory.
+pub unsafe fn write_foreign_snapshot<M, W>(tensor: &Foreign<M>, writer: W) -> Result<(), Error>
+where
+    M: ManagedTensorBase,
+    W: Write,
+{
+    unsafe { write_tensor(tensor.tensor(), tensor.version(), tensor.flags(), writer) }
+}
+
+/// Reads a snapshot as a compact, owned CPU tensor.
+///
+/// The tensor keeps the recorded dtype, shape, `READ_ONLY` and
+/// `IS_SUBBYTE_TYPE_PADDED` flags, and is marked `IS_COPIED`. The original
+/// strides, offset, device and version are only kept in the snapshot; use
+/// [`read_snapshot_with_meta`] to get them.
+pub fn read_snapshot<M, R>(reader: R) -> Result<Local<M>, Error>
+where
+    M: ManagedTensorBase,
+    R: Read,
+{
+    read_snapshot_with_meta(reader).map(|(tensor, _)| tensor)
+}
+
+/// Reads a snapshot, also returning the descriptor as it was recorded.
+pub fn read_snapshot_with_meta<M, R>(reader: R) -> Result<(Local<M>, TensorMeta), Error>
+where
+    M: ManagedTensorBase,
+    R: Read,
+{
+    let mut input = Checksummed::new(reader);
+    let meta = read_meta(&mut input)?;
+
+    let expected = packed_len(&meta)?;
+    let len = input.u64()?;
+    if len != expected as u64 {
+        return Err(Error::Malformed {
+            reason: "data length does not match shape and dtype",
+        });
+    }
+    // The header is untrusted, so the buffer grows with the data actually
+    // read instead of being allocated from `expected` up front.
+    let mut data = AlignedBuffer::read_exact_from(&mut input, expected).context(IoSnafu)?;
+
+    let computed = input.crc.finish();
+    let stored = u32::from_le_bytes(input.array().context(IoSnafu)?);
+    if stored != computed {
+        return Err(Error::ChecksumMismatch { stored, computed });
+    }
+
+    let strides = tensor::compact_strides(&meta.shape)?;
+    let data_ptr = if expected == 0 {
+        std::ptr::null_mut()
+    } else {
+        data.as_mut_ptr().cast()
+    };
+    let prepared = Dynamic::new(Copied(&meta.shape[..]), Copied(strides)).prepare::<M>()?;
+    let mut initialized = prepared
+        .initialize(Box::new(data))
+        .map_err(metadata::Error::from)?;
+    initialized.set_data(data_ptr);
+    initialized.set_dtype(meta.dtype);
+    initialized.set_device(DLDevice::CPU);
+    let kept = meta.flags & (DlpackFlags::READ_ONLY | DlpackFlags::IS_SUBBYTE_TYPE_PADDED);
+    // SAFETY: the buffer is owned by the context.
+    initialized.set_flags_unchecked(kept | DlpackFlags::IS_COPIED);
+    // SAFETY: the compact descriptor addresses exactly the owned buffer.
+    Ok((unsafe { initialized.finish() }, meta))
+}
+
+unsafe fn write_tensor<W: Write>(
+    tensor: &DLTensor,
+    version: Option<DLPackVersion>,
+    flags: DlpackFlags,
+    writer: W,
+) -> Result<(), Error> {
+    tensor.ensure_host(crate::HostAccess::STRICT)?;
+    let meta = unsafe { TensorMeta::from_tensor(tensor, version, flags) }?;
+    let data = unsafe { tensor.gather_bytes() }?;
+
+    let mut output = Checksummed::new(writer);
+    output.put(&MAGIC)?;
+    output.put(&FORMAT.to_le_bytes())?;
+    match meta.version {
+        Some(version) => {
+            output.put(&[1])?;
+            output.put(&version.major.to_le_bytes())?;
+            output.put(&version.minor.to_le_bytes())?;
+        }
+        None => output.put(&[0; 9])?,
+    }
+    output.put(&meta.flags.bits().to_le_bytes())?;
+    output.put(&[meta.dtype.code.0, meta.dtype.bits])?;
+    output.put(&meta.dtype.lanes.to_le_bytes())?;
+    output.put(&meta.device.device_type.0.to_le_bytes())?;
+    output.put(&meta.device.device_id.to_le_bytes())?;
+    output.put(&meta.byte_offset.to_le_bytes())?;
+    output.put(&(meta.shape.len() as u32).to_le_bytes())?;
+    for dim in &meta.shape {
+        output.put(&dim.to_le_bytes())?;
+    }
+    output.put(&[u8::from(meta.strides.is_some())])?;
+    for stride in meta.strides.iter().flatten() {
+        output.put(&stride.to_le_bytes())?;
+    }
+    output.put(&(data.len() as u64).to_le_bytes())?;
+    output.put(&data)?;
+    let checksum = output.crc.finish();
+    output
+        .inner
+        .write_all(&checksum.to_le_bytes())
+        .context(IoSnafu)?;
+    output.inner.flush().context(IoSnafu)
+}
+
+fn read_meta<R: Read>(input: &mut Checksummed<R>) -> Result<TensorMeta, Error> {
+    if input.array::<8>().context(IoSnafu)? != MAGIC {
+        return Err(Error::BadMagic);
+    }
+    let format = input.u32()?;
+    if format != FORMAT {
+        return Err(Error::UnsupportedFormat { format });
+    }
+    let [has_version] = input.array().context(IoSnafu)?;
+    let (major, minor) = (input.u32()?, input.u32()?);
+    let version = match has_version {
+        0 => None,
+        1 => Some(DLPackVersion { major, minor }),
+        _ => {
+            return Err(Error::Malformed {
+                reason: "bad version marker",
+            });
+        }
+    };
+    let flags = DlpackFlags::from_bits_retain(input.u64()?);
+    let [code, bits] = input.array().context(IoSnafu)?;
+    let dtype = DLDataType {
+        code: DLDataTypeCode(code),
+        bits,
+        lanes: u16::from_le_bytes(input.array().context(IoSnafu)?),
+    };
+    let device = DLDevice {
+        device_type: DLDeviceType(input.u32()?),
+        device_id: i32::from_le_bytes(input.array().context(IoSnafu)?),
+    };
+    let byte_offset = input.u64()?;
+    let ndim = input.u32()?;
+    let shape = (0..ndim)
+        .map(|_| input.i64())
+        .collect::<Result<Vec<_>, _>>()?;
+    let strides = match input.array::<1>().context(IoSnafu)? {
+        [0] => None,
+        [1] => Some(
+            (0..ndim)
+                .map(|_| input.i64())
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        _ => {
+            return Err(Error::Malformed {
+                reason: "bad strides marker",
+            });
+        }
+    };
+    Ok(TensorMeta {
+        shape,
+        strides,
+        dtype,
+        device,
+        byte_offset,
+        version,
+        flags,
+    })
+}
+
+/// Returns the byte length of `meta`'s elements packed in row-major order.
+fn packed_len(meta: &TensorMeta) -> Result<usize, Error> {
+    let mut count = 1usize;
+    for (axis, &value) in meta.shape.iter().enumerate() {
+        let dim =
+            usize::try_from(value).map_err(|_| metadata::Error::NegativeShape { axis, value })?;
+        count = count
+            .checked_mul(dim)
+            .ok_or(metadata::Error::SpanOverflow)?;
+    }
+    let bits = usize::from(meta.dtype.bits) * usize::from(meta.dtype.lanes);
+    Ok(count
+        .checked_mul(bits)
+        .ok_or(metadata::Error::SpanOverflow)?
+        .div_ceil(8))
+}
+
+/// Wraps a reader or writer and checksums every byte passing through.
+struct Checksummed<T> {
+    inner: T,
+    crc: Crc32,
+}
+
+impl<T> Checksummed<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            crc: Crc32::new(),
+        }
+    }
+}
+
+impl<W: Write> Checksummed<W> {
+    fn put(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.crc.update(bytes);
+        self.inner.write_all(bytes).context(IoSnafu)
+    }
+}
+
+impl<R: Read> Read for Checksummed<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.crc.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<R: Read> Checksummed<R> {
+    fn array<const N: usize>(&mut self) -> std::io::Result<[u8; N]> {
+        let mut bytes = [0; N];
+        self.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_le_bytes(self.array().context(IoSnafu)?))
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_le_bytes(self.array().context(IoSnafu)?))
+    }
+
+    fn i64(&mut self) -> Result<i64, Error> {
+        Ok(i64::from_le_bytes(self.array().context(IoSnafu)?))
+    }
+}
+
+/// Bitwise CRC-32 (IEEE 802.3, reflected polynomial `0xEDB88320`).
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Self(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u32::from(byte);
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        allocation::fixed::make_test_tensor,
+        ffi::{DLManagedTensor, DLManagedTensorVersioned},
+    };
+
+    #[test]
+    fn crc32_matches_the_reference_check_value() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn strided_tensor_round_trips_with_its_original_layout() {
+        // A transposed 2x3 view into a padded buffer, starting one element in.
+        let storage = [-1.0_f32, 1.0, 4.0, 2.0, 5.0, 3.0, 6.0];
+        let tensor = make_test_tensor::<_, DLManagedTensorVersioned, 2>(
+            Box::new(()),
+            storage.as_ptr().cast_mut().cast(),
+            DLDataType::F32,
+            DLDevice::CPU,
+            [2, 3],
+            [1, 2],
+            DlpackFlags::READ_ONLY,
+        );
+        unsafe { (*tensor.as_ptr()).dl_tensor.byte_offset = 4 };
+
+        let mut bytes = Vec::new();
+        write_snapshot(&tensor, &mut bytes).unwrap();
+        let (restored, meta) =
+            read_snapshot_with_meta::<DLManagedTensorVersioned, _>(bytes.as_slice()).unwrap();
+
+        assert_eq!(meta, tensor.meta().unwrap());
+        assert_eq!(meta.byte_offset, 4);
+        assert_eq!(restored.shape().unwrap(), &[2, 3]);
+        assert!(restored.is_compact().unwrap());
+        assert_eq!(
+            restored.cpu_slice::<f32>().unwrap(),
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+        assert_eq!(
+            restored.flags(),
+            DlpackFlags::READ_ONLY | DlpackFlags::IS_COPIED
+        );
+
+        let foreign = tensor.into_foreign();
+        let mut again = Vec::new();
+        unsafe { write_foreign_snapshot(&foreign, &mut again) }.unwrap();
+        assert_eq!(again, bytes);
+    }
+
+    #[test]
+    fn packed_fp8_and_legacy_metadata_are_preserved() {
+        let data = [0x38_u8, 0x40, 0x44, 0x48];
+        let tensor = make_test_tensor::<_, DLManagedTensor, 1>(
+            Box::new(()),
+            data.as_ptr().cast_mut().cast(),
+            DLDataType::F8E4M3FN,
+            DLDevice::CPU,
+            [4],
+            [1],
+            DlpackFlags::empty(),
+        );
+
+        let mut bytes = Vec::new();
+        write_snapshot(&tensor, &mut bytes).unwrap();
+        let (restored, meta) =
+            read_snapshot_with_meta::<DLManagedTensor, _>(bytes.as_slice()).unwrap();
+        assert_eq!(meta.version, None);
+        assert_eq!(restored.dtype(), DLDataType::F8E4M3FN);
+        assert_eq!(restored.cpu_bytes().unwrap(), &data);
+    }
+
+    #[test]
+    fn corruption_and_foreign_devices_are_rejected() {
+        let tensor = Local::<DLManagedTensorVersioned>::from_vec(vec![1_i32, 2, 3], &[3]).unwrap();
+        let mut bytes = Vec::new();
+        write_snapshot(&tensor, &mut bytes).unwrap();
+
+        let mut flipped = bytes.clone();
+        let last_data_byte = flipped.len() - 5;
+        flipped[last_data_byte] ^= 1;
+        assert!(matches!(
+            read_snapshot::<DLManagedTensorVersioned, _>(flipped.as_slice()),
+            Err(Error::ChecksumMismatch { .. })
+        ));
+        assert!(matches!(
+            read_snapshot::<DLManagedTensorVersioned, _>(&bytes[..bytes.len() - 2]),
+            Err(Error::Io { .. })
+        ));
+        assert!(matches!(
+            read_snapshot::<DLManagedTensorVersioned, _>(&b"NOTASNAPSHOT"[..]),
+            Err(Error::BadMagic)
+        ));
+
+        // A header claiming 2^40 elements fails on the missing data instead
+        // of allocating for them. The data length precedes the 12 data bytes
+        // and the checksum.
+        let shape_at = 53;
+        let len_at = bytes.len() - 4 - 12 - 8;
+        let mut oversized = bytes.clone();
+        oversized[shape_at..shape_at + 8].copy_from_slice(&(1_i64 << 40).to_le_bytes());
+        oversized[len_at..len_at + 8].copy_from_slice(&(4_u64 << 40).to_le_bytes());
+        assert!(matches!(
+            read_snapshot::<DLManagedTensorVersioned, _>(oversized.as_slice()),
+            Err(Error::Io { source }) if source.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+
+        let mut data = [0_u8; 4];
+        let cuda = make_test_tensor::<_, DLManagedTensorVersioned, 1>(
+            Box::new(()),
+            data.as_mut_ptr().cast(),
+            DLDataType::U8,
+            DLDevice::cuda(0),
+            [4],
+            [1],
+            DlpackFlags::empty(),
+        );
+        assert!(matches!(
+            write_snapshot(&cuda, Vec::new()),
+            Err(Error::Tensor {
+                source: tensor::Error::NotCpu { .. }
+            })
+        ));
+    }
+}
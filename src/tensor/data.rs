@@ -1,5 +1,5 @@
 use super::*;
-use crate::DlpackElement;
+use crate::{DlpackElement, HostAccess};
 use snafu::ensure;
 use std::{mem, os::raw::c_void};
 
@@ -21,8 +21,30 @@ impl DLTensor {
     /// In addition to valid shape and strides metadata, the byte-offset-adjusted
     /// data pointer must reference `num_elements` initialized values of `T`
     /// that remain readable for the returned slice's lifetime.
+    #[inline]
     pub unsafe fn cpu_slice<T: DlpackElement>(&self) -> Result<&[T], Error> {
-        self.ensure_cpu()?;
+        unsafe { self.cpu_slice_with_policy(HostAccess::STRICT) }
+    }
+
+    /// Returns host-accessible tensor data as a typed Rust slice.
+    ///
+    /// Like [`Self::cpu_slice`], but also accepts the non-CPU device types
+    /// allowed by `policy`, such as pinned or managed memory.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::NotCpu`] if `policy` does not allow the tensor's device.
+    /// - The remaining errors of [`Self::cpu_slice`].
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_slice`], any device
+    /// work writing the data must have completed before it is read.
+    pub unsafe fn cpu_slice_with_policy<T: DlpackElement>(
+        &self,
+        policy: HostAccess,
+    ) -> Result<&[T], Error> {
+        self.ensure_host(policy)?;
         ensure!(
             self.dtype.is::<T>(),
             DtypeMismatchSnafu {
@@ -62,13 +84,87 @@ impl DLTensor {
     /// lifetime.
     #[inline]
     pub unsafe fn cpu_bytes(&self) -> Result<&[u8], Error> {
-        self.ensure_cpu()?;
+        unsafe { self.cpu_bytes_with_policy(HostAccess::STRICT) }
+    }
+
+    /// Returns compact host-accessible tensor data as raw bytes.
+    ///
+    /// Like [`Self::cpu_bytes`], but also accepts the non-CPU device types
+    /// allowed by `policy`.
+    ///
+    /// # Safety
+    ///
+    /// In addition to the requirements of [`Self::cpu_bytes`], any device
+    /// work writing the data must have completed before it is read.
+    pub unsafe fn cpu_bytes_with_policy(&self, policy: HostAccess) -> Result<&[u8], Error> {
+        self.ensure_host(policy)?;
         ensure!(unsafe { self.is_compact()? }, NonCompactStridesSnafu);
         let len = unsafe { self.num_bytes()? };
         let data = unsafe { self.offset_bytes_ptr()? };
         Ok(unsafe { std::slice::from_raw_parts(data, len) })
     }
 
+    /// Copies the addressed CPU elements into a row-major byte vector.
+    ///
+    /// Compact tensors are copied in one go; any other layout, including
+    /// negative strides, is gathered element by element. Packed sub-byte
+    /// dtypes must be compact.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::cpu_bytes`], for every element the strides address.
+    pub(crate) unsafe fn gather_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut out = vec![0; unsafe { self.num_bytes()? }];
+        unsafe { self.gather_bytes_into(HostAccess::STRICT, &mut out) }?;
+        Ok(out)
+    }
+
+    /// Copies the addressed host-accessible elements into `out` in row-major
+    /// order, as [`Self::gather_bytes`] does.
+    ///
+    /// `out` must be [`Self::num_bytes`] long.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Self::cpu_bytes_with_policy`], for every element the strides
+    /// address.
+    pub(crate) unsafe fn gather_bytes_into(
+        &self,
+        policy: HostAccess,
+        out: &mut [u8],
+    ) -> Result<(), Error> {
+        if unsafe { self.is_compact()? } {
+            out.copy_from_slice(unsafe { self.cpu_bytes_with_policy(policy)? });
+            return Ok(());
+        }
+        self.ensure_host(policy)?;
+        let bits = usize::from(self.dtype.bits) * usize::from(self.dtype.lanes);
+        ensure!(bits.is_multiple_of(8), NonCompactStridesSnafu);
+        let size = bits / 8;
+        if size == 0 {
+            // Zero-width elements occupy no bytes, so there is nothing to copy.
+            return Ok(());
+        }
+        let shape = unsafe { self.shape()? };
+        let strides = unsafe { self.strides_or_compact()? };
+        let base = unsafe { self.offset_bytes_ptr()? };
+
+        let mut index = vec![0i64; shape.len()];
+        for element in out.chunks_exact_mut(size) {
+            let offset: i64 = index.iter().zip(strides.iter()).map(|(i, s)| i * s).sum();
+            let start = unsafe { base.offset(offset as isize * size as isize) };
+            element.copy_from_slice(unsafe { std::slice::from_raw_parts(start, size) });
+            for axis in (0..index.len()).rev() {
+                index[axis] += 1;
+                if index[axis] < shape[axis] {
+                    break;
+                }
+                index[axis] = 0;
+            }
+        }
+        Ok(())
+    }
+
     /// Returns the byte-offset-adjusted data pointer for typed consumers.
     ///
     /// This validates dtype, nullness for non-empty tensors, offset, and
@@ -147,9 +243,9 @@ impl DLTensor {
         self.data as *const c_void
     }
 
-    pub(crate) fn ensure_cpu(&self) -> Result<(), Error> {
+    pub(crate) fn ensure_host(&self, policy: HostAccess) -> Result<(), Error> {
         ensure!(
-            self.device.device_type == DLDeviceType::CPU,
+            policy.allows(self.device.device_type),
             NotCpuSnafu {
                 device_type: self.device.device_type
             }
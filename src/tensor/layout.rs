@@ -64,6 +64,46 @@ pub fn is_compact_strides(shape: &[i64], strides: Option<&[i64]>) -> Result<bool
     Ok(strides == compact_strides(shape)?.as_slice())
 }
 
+/// Returns whether strides describe compact column-major (Fortran) layout.
+///
+/// A null DLPack strides pointer means compact row-major, which counts as
+/// column-major only when both orders produce the same strides, as for
+/// scalars and vectors.
+pub fn is_fortran_strides(shape: &[i64], strides: Option<&[i64]>) -> Result<bool, Error> {
+    validate_shape_dimensions(shape)?;
+    let row_major;
+    let strides = match strides {
+        Some(strides) => {
+            ensure!(
+                shape.len() == strides.len(),
+                MismatchedStridesSnafu {
+                    shape_len: shape.len(),
+                    strides_len: strides.len()
+                }
+            );
+            strides
+        }
+        None => {
+            row_major = compact_strides(shape)?;
+            row_major.as_slice()
+        }
+    };
+    if shape.contains(&0) {
+        return Ok(true);
+    }
+
+    let mut expected = 1i64;
+    for (&dim, &stride) in shape.iter().zip(strides) {
+        if stride != expected {
+            return Ok(false);
+        }
+        expected = expected
+            .checked_mul(dim)
+            .ok_or(Error::NumElementsOverflow)?;
+    }
+    Ok(true)
+}
+
 fn validate_shape_dimensions(shape: &[i64]) -> Result<(), Error> {
     for (axis, &value) in shape.iter().enumerate() {
         ensure!(value >= 0, NegativeDimensionSnafu { axis, value });
@@ -0,0 +1,123 @@
+use super::Error;
+use crate::DlpackFlags;
+use crate::ffi::{DLDataType, DLDevice, DLPackVersion, DLTensor};
+
+/// An owned copy of a tensor's descriptor, without its data.
+///
+/// Produced by [`Local::meta`](crate::Local::meta) and
+/// [`Foreign::meta`](crate::Foreign::meta). With the `serde` feature it
+/// serializes using readable dtype and device names, for example
+/// `{"dtype": "float32", "device": "cuda:0", ...}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TensorMeta {
+    pub shape: Vec<i64>,
+    /// Explicit strides in elements, or `None` for implicit compact strides.
+    pub strides: Option<Vec<i64>>,
+    pub dtype: DLDataType,
+    pub device: DLDevice,
+    pub byte_offset: u64,
+    /// The declared ABI version, or `None` for the legacy ABI.
+    pub version: Option<DLPackVersion>,
+    /// Versioned flags; always empty for the legacy ABI.
+    pub flags: DlpackFlags,
+}
+
+impl TensorMeta {
+    /// Copies the descriptor fields of `tensor`.
+    ///
+    /// # Safety
+    ///
+    /// `tensor.shape` and `tensor.strides` must describe readable metadata for
+    /// `tensor.ndim` dimensions.
+    pub(crate) unsafe fn from_tensor(
+        tensor: &DLTensor,
+        version: Option<DLPackVersion>,
+        flags: DlpackFlags,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            shape: unsafe { tensor.shape() }?.to_vec(),
+            strides: unsafe { tensor.strides() }?.map(<[i64]>::to_vec),
+            dtype: tensor.dtype,
+            device: tensor.device,
+            byte_offset: tensor.byte_offset,
+            version,
+            flags,
+        })
+    }
+}
+
+/// A validated, owned snapshot of a tensor's descriptor together with the
+/// layout facts derived from it.
+///
+/// Produced by [`Local::info`](crate::Local::info) and
+/// [`Foreign::info`](crate::Foreign::info). `Display` prints a one-line
+/// summary such as `float32[2,3,224,224] cuda:0 strides=[1,2,6,1344] RO`;
+/// strides are only listed when the layout is not compact row-major.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TensorInfo {
+    pub meta: TensorMeta,
+    pub num_elements: usize,
+    pub num_bytes: usize,
+    /// Whether the layout is compact row-major (C order).
+    pub is_compact: bool,
+    /// Whether the layout is compact column-major (Fortran order).
+    pub is_fortran: bool,
+}
+
+impl TensorInfo {
+    /// Copies and validates the descriptor fields of `tensor`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`TensorMeta::from_tensor`].
+    pub(crate) unsafe fn from_tensor(
+        tensor: &DLTensor,
+        version: Option<DLPackVersion>,
+        flags: DlpackFlags,
+    ) -> Result<Self, Error> {
+        let meta = unsafe { TensorMeta::from_tensor(tensor, version, flags) }?;
+        let strides = meta.strides.as_deref();
+        Ok(Self {
+            num_elements: unsafe { tensor.num_elements() }?,
+            num_bytes: unsafe { tensor.num_bytes() }?,
+            is_compact: super::is_compact_strides(&meta.shape, strides)?,
+            is_fortran: super::is_fortran_strides(&meta.shape, strides)?,
+            meta,
+        })
+    }
+}
+
+impl std::fmt::Display for TensorInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let meta = &self.meta;
+        write!(f, "{}[{}] {}", meta.dtype, join(&meta.shape), meta.device)?;
+        if let Some(strides) = &meta.strides
+            && !self.is_compact
+        {
+            write!(f, " strides=[{}]", join(strides))?;
+        }
+        if meta.byte_offset != 0 {
+            write!(f, " offset={}", meta.byte_offset)?;
+        }
+        for (flag, label) in [
+            (DlpackFlags::READ_ONLY, "RO"),
+            (DlpackFlags::IS_COPIED, "COPIED"),
+            (DlpackFlags::IS_SUBBYTE_TYPE_PADDED, "PADDED"),
+        ] {
+            if meta.flags.contains(flag) {
+                write!(f, " {label}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn join(values: &[i64]) -> String {
+    values
+        .iter()
+        .map(i64::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
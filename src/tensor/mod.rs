@@ -5,8 +5,12 @@ use snafu::Snafu;
 
 mod data;
 mod layout;
+mod meta;
+mod print;
 
-pub use layout::{compact_strides, compact_strides_array, is_compact_strides};
+pub use layout::{compact_strides, compact_strides_array, is_compact_strides, is_fortran_strides};
+pub use meta::{TensorInfo, TensorMeta};
+pub use print::{PrintOptions, TensorDisplay};
 
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -34,7 +38,9 @@ pub enum Error {
     #[snafu(display("a contiguous Rust slice requires compact row-major strides"))]
     NonCompactStrides,
 
-    #[snafu(display("tensor must be on CPU to expose a Rust slice, got {device_type:?}"))]
+    #[snafu(display(
+        "tensor must be in host-accessible memory to expose a Rust slice, got {device_type:?}"
+    ))]
     NotCpu { device_type: DLDeviceType },
 
     #[snafu(display("dtype mismatch: expected {expected:?}, got {actual:?}"))]
@@ -231,6 +237,25 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn gathering_zero_width_elements_copies_nothing() {
+        let data = [0u8; 4];
+        let shape = [2i64, 2];
+        let strides = [1i64, 2];
+        let tensor = DLTensor {
+            data: data.as_ptr() as *mut _,
+            device: DLDevice::CPU,
+            ndim: 2,
+            dtype: DLDataType::scalar(DLDataTypeCode::UINT, 0),
+            shape: shape.as_ptr() as *mut i64,
+            strides: strides.as_ptr() as *mut i64,
+            ..DLTensor::default()
+        };
+
+        assert!(!unsafe { tensor.is_compact() }.unwrap());
+        assert!(unsafe { tensor.gather_bytes() }.unwrap().is_empty());
+    }
+
     #[test]
     fn empty_tensor_is_compact_regardless_of_strides() {
         let shape = [2i64, 0];
@@ -251,4 +276,18 @@ mod tests {
             Err(Error::NegativeDimension { .. })
         ));
     }
+
+    #[test]
+    fn fortran_strides_are_compact_column_major() {
+        assert!(is_fortran_strides(&[2, 3, 4], Some(&[1, 2, 6])).unwrap());
+        assert!(!is_fortran_strides(&[2, 3, 4], Some(&[12, 4, 1])).unwrap());
+        assert!(!is_fortran_strides(&[2, 3], None).unwrap());
+        assert!(is_fortran_strides(&[5], None).unwrap());
+        assert!(is_fortran_strides(&[], None).unwrap());
+        assert!(is_fortran_strides(&[2, 0], Some(&[7, 7])).unwrap());
+        assert!(matches!(
+            is_fortran_strides(&[2, 3], Some(&[1])),
+            Err(Error::MismatchedStrides { .. })
+        ));
+    }
 }
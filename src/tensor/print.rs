@@ -0,0 +1,583 @@
+//! NumPy-style formatting of CPU tensor contents.
+
+use super::TensorInfo;
+use crate::ffi::{DLDataType, DLDataTypeCode, DLPackVersion, DLTensor};
+use crate::{DlpackFlags, HostAccess};
+use std::fmt;
+
+/// Formatting options for [`TensorDisplay`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Digits after the decimal point for floating-point and complex values.
+    pub precision: usize,
+    /// Minimum field width for every element. `None` right-aligns elements
+    /// to the widest printed value.
+    pub width: Option<usize>,
+    /// Tensors with more elements than this are summarized with `...`.
+    pub threshold: usize,
+    /// Number of leading and trailing items kept per axis when summarizing.
+    pub edge_items: usize,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            precision: 4,
+            width: None,
+            threshold: 1000,
+            edge_items: 3,
+        }
+    }
+}
+
+/// Displays tensor contents as nested, comma-separated brackets.
+///
+/// Created by [`Local::display`](crate::Local::display) and
+/// [`Foreign::display`](crate::Foreign::display). Strided layouts are walked
+/// element by element, so non-compact tensors print in logical order. Tensors
+/// whose data is not on the CPU, or whose dtype has no scalar element
+/// representation (vector lanes and packed sub-byte types), fall back to the
+/// one-line [`TensorInfo`] summary. FP8 elements are widened to `f64` and
+/// printed like other floats.
+pub struct TensorDisplay<'a> {
+    tensor: &'a DLTensor,
+    version: Option<DLPackVersion>,
+    flags: DlpackFlags,
+    options: PrintOptions,
+}
+
+impl<'a> TensorDisplay<'a> {
+    /// # Safety
+    ///
+    /// The descriptor's metadata must be readable and, for CPU tensors, every
+    /// element addressed by its shape, strides and byte offset must be
+    /// readable and initialized while the formatter is in use.
+    pub(crate) unsafe fn new(
+        tensor: &'a DLTensor,
+        version: Option<DLPackVersion>,
+        flags: DlpackFlags,
+    ) -> Self {
+        Self {
+            tensor,
+            version,
+            flags,
+            options: PrintOptions::default(),
+        }
+    }
+
+    /// Replaces the formatting options.
+    pub fn with_options(mut self, options: PrintOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
+
+impl fmt::Display for TensorDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let info = match unsafe { TensorInfo::from_tensor(self.tensor, self.version, self.flags) } {
+            Ok(info) => info,
+            Err(error) => return write!(f, "<invalid tensor: {error}>"),
+        };
+        let Some(kind) = ElementKind::of(self.tensor.dtype) else {
+            return write!(f, "{info}");
+        };
+        if self.tensor.ensure_host(HostAccess::STRICT).is_err() {
+            return write!(f, "{info}");
+        }
+        if info.num_elements == 0 {
+            return f.write_str("[]");
+        }
+        let data = match unsafe { self.tensor.offset_bytes_ptr() } {
+            Ok(data) => data,
+            Err(error) => return write!(f, "<invalid tensor: {error}>"),
+        };
+        let strides = match unsafe { self.tensor.strides_or_compact() } {
+            Ok(strides) => strides,
+            Err(error) => return write!(f, "<invalid tensor: {error}>"),
+        };
+
+        let walker = Walker {
+            shape: &info.meta.shape,
+            strides: &strides,
+            summarize: info.num_elements > self.options.threshold,
+            edge_items: self.options.edge_items,
+        };
+        let read = |offset: isize| {
+            let element = data.wrapping_offset(offset * kind.size() as isize);
+            unsafe { kind.format(element, self.options.precision) }
+        };
+
+        let mut cells = Vec::new();
+        walker.collect(0, 0, &mut |offset| cells.push(read(offset)));
+        let natural = cells.iter().map(String::len).max().unwrap_or(0);
+        let width = self.options.width.unwrap_or(natural);
+
+        let mut cells = cells.into_iter();
+        walker.write(f, 0, 0, &mut |f| {
+            let cell = cells.next().unwrap_or_default();
+            write!(f, "{cell:>width$}")
+        })
+    }
+}
+
+/// Visits the elements kept after summarization in logical order.
+struct Walker<'a> {
+    shape: &'a [i64],
+    strides: &'a [i64],
+    summarize: bool,
+    edge_items: usize,
+}
+
+impl Walker<'_> {
+    /// Indices printed along `axis`; `None` marks the elided middle.
+    fn visible(&self, axis: usize) -> Vec<Option<usize>> {
+        let len = self.shape[axis] as usize;
+        if self.summarize && len > 2 * self.edge_items {
+            (0..self.edge_items)
+                .map(Some)
+                .chain(std::iter::once(None))
+                .chain((len - self.edge_items..len).map(Some))
+                .collect()
+        } else {
+            (0..len).map(Some).collect()
+        }
+    }
+
+    fn child_offset(&self, axis: usize, offset: isize, index: usize) -> isize {
+        offset + index as isize * self.strides[axis] as isize
+    }
+
+    fn collect(&self, axis: usize, offset: isize, visit: &mut impl FnMut(isize)) {
+        if axis == self.shape.len() {
+            return visit(offset);
+        }
+        for index in self.visible(axis).into_iter().flatten() {
+            self.collect(axis + 1, self.child_offset(axis, offset, index), visit);
+        }
+    }
+
+    fn write(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        axis: usize,
+        offset: isize,
+        cell: &mut impl FnMut(&mut fmt::Formatter<'_>) -> fmt::Result,
+    ) -> fmt::Result {
+        let ndim = self.shape.len();
+        if axis == ndim {
+            return cell(f);
+        }
+
+        f.write_str("[")?;
+        for (position, index) in self.visible(axis).into_iter().enumerate() {
+            if position > 0 {
+                if axis + 1 == ndim {
+                    f.write_str(", ")?;
+                } else {
+                    // One line break per remaining axis, then align under the
+                    // opening bracket of this axis.
+                    f.write_str(",")?;
+                    for _ in axis + 1..ndim {
+                        f.write_str("\n")?;
+                    }
+                    write!(f, "{:1$}", "", axis + 1)?;
+                }
+            }
+            match index {
+                Some(index) => {
+                    self.write(f, axis + 1, self.child_offset(axis, offset, index), cell)?
+                }
+                None => f.write_str("...")?,
+            }
+        }
+        f.write_str("]")
+    }
+}
+
+/// Scalar element representations that can be printed.
+#[derive(Clone, Copy)]
+enum ElementKind {
+    Int(u8),
+    UInt(u8),
+    Float(u8),
+    BFloat16,
+    Float8(Minifloat),
+    Complex(u8),
+    Bool,
+}
+
+impl ElementKind {
+    fn of(dtype: DLDataType) -> Option<Self> {
+        if dtype.lanes != 1 {
+            return None;
+        }
+        let kind = match (dtype.code, dtype.bits) {
+            (DLDataTypeCode::INT, 8 | 16 | 32 | 64) => Self::Int(dtype.bits),
+            (DLDataTypeCode::UINT, 8 | 16 | 32 | 64) => Self::UInt(dtype.bits),
+            (DLDataTypeCode::FLOAT, 16 | 32 | 64) => Self::Float(dtype.bits),
+            (DLDataTypeCode::BFLOAT, 16) => Self::BFloat16,
+            (DLDataTypeCode::COMPLEX, 64 | 128) => Self::Complex(dtype.bits),
+            (DLDataTypeCode::BOOL, 8) => Self::Bool,
+            (code, 8) => Self::Float8(Minifloat::float8(code)?),
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::Int(bits) | Self::UInt(bits) | Self::Float(bits) | Self::Complex(bits) => {
+                bits as usize / 8
+            }
+            Self::BFloat16 => 2,
+            Self::Float8(_) | Self::Bool => 1,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must reference a readable element of this kind. It need not be
+    /// aligned.
+    unsafe fn format(self, ptr: *const u8, precision: usize) -> String {
+        unsafe fn read<T>(ptr: *const u8) -> T {
+            unsafe { ptr.cast::<T>().read_unaligned() }
+        }
+
+        unsafe {
+            match self {
+                Self::Int(8) => read::<i8>(ptr).to_string(),
+                Self::Int(16) => read::<i16>(ptr).to_string(),
+                Self::Int(32) => read::<i32>(ptr).to_string(),
+                Self::Int(_) => read::<i64>(ptr).to_string(),
+                Self::UInt(8) => read::<u8>(ptr).to_string(),
+                Self::UInt(16) => read::<u16>(ptr).to_string(),
+                Self::UInt(32) => read::<u32>(ptr).to_string(),
+                Self::UInt(_) => read::<u64>(ptr).to_string(),
+                Self::Float(16) => format_float(Minifloat::F16.decode(read(ptr)), precision),
+                Self::Float(32) => format_float(read::<f32>(ptr).into(), precision),
+                Self::Float(_) => format_float(read::<f64>(ptr), precision),
+                Self::BFloat16 => {
+                    let value = f32::from_bits(u32::from(read::<u16>(ptr)) << 16);
+                    format_float(value.into(), precision)
+                }
+                Self::Float8(float8) => {
+                    format_float(float8.decode(read::<u8>(ptr).into()), precision)
+                }
+                Self::Complex(64) => {
+                    let [re, im] = read::<[f32; 2]>(ptr);
+                    format_complex(re.into(), im.into(), precision)
+                }
+                Self::Complex(_) => {
+                    let [re, im] = read::<[f64; 2]>(ptr);
+                    format_complex(re, im, precision)
+                }
+                Self::Bool => {
+                    let value = if read::<u8>(ptr) != 0 {
+                        "True"
+                    } else {
+                        "False"
+                    };
+                    value.to_owned()
+                }
+            }
+        }
+    }
+}
+
+fn format_float(value: f64, precision: usize) -> String {
+    if value.is_nan() {
+        "nan".to_owned()
+    } else if value == f64::INFINITY {
+        "inf".to_owned()
+    } else if value == f64::NEG_INFINITY {
+        "-inf".to_owned()
+    } else {
+        format!("{value:.precision$}")
+    }
+}
+
+fn format_complex(re: f64, im: f64, precision: usize) -> String {
+    let sign = if im.is_sign_negative() { '-' } else { '+' };
+    format!(
+        "{}{sign}{}j",
+        format_float(re, precision),
+        format_float(im.abs(), precision)
+    )
+}
+
+/// How a small float format encodes infinities and NaN.
+#[derive(Clone, Copy)]
+enum Special {
+    /// IEEE 754: an all-ones exponent holds infinities and NaNs.
+    Ieee,
+    /// Finite (`fn`): only the all-ones bit pattern, either sign, is NaN.
+    Finite,
+    /// Finite without negative zero (`fnuz`): the negative zero pattern is
+    /// the only NaN.
+    FiniteUnsignedZero,
+    /// Exponent only and unsigned (`e8m0fnu`): the all-ones pattern is NaN.
+    ExponentOnly,
+}
+
+/// A binary floating-point format of at most 16 bits, widened to `f64`
+/// without depending on the `half` feature or a float8 crate.
+#[derive(Clone, Copy)]
+struct Minifloat {
+    exponent_bits: u32,
+    mantissa_bits: u32,
+    bias: i32,
+    special: Special,
+}
+
+impl Minifloat {
+    const F16: Self = Self::new(5, 10, 15, Special::Ieee);
+
+    const fn new(exponent_bits: u32, mantissa_bits: u32, bias: i32, special: Special) -> Self {
+        Self {
+            exponent_bits,
+            mantissa_bits,
+            bias,
+            special,
+        }
+    }
+
+    /// The layout of the FP8 type `code`, following the `ml_dtypes` names.
+    fn float8(code: DLDataTypeCode) -> Option<Self> {
+        let format = match code {
+            DLDataTypeCode::FLOAT8_E3M4 => Self::new(3, 4, 3, Special::Ieee),
+            DLDataTypeCode::FLOAT8_E4M3 => Self::new(4, 3, 7, Special::Ieee),
+            DLDataTypeCode::FLOAT8_E4M3B11FNUZ => Self::new(4, 3, 11, Special::FiniteUnsignedZero),
+            DLDataTypeCode::FLOAT8_E4M3FN => Self::new(4, 3, 7, Special::Finite),
+            DLDataTypeCode::FLOAT8_E4M3FNUZ => Self::new(4, 3, 8, Special::FiniteUnsignedZero),
+            DLDataTypeCode::FLOAT8_E5M2 => Self::new(5, 2, 15, Special::Ieee),
+            DLDataTypeCode::FLOAT8_E5M2FNUZ => Self::new(5, 2, 16, Special::FiniteUnsignedZero),
+            DLDataTypeCode::FLOAT8_E8M0FNU => Self::new(8, 0, 127, Special::ExponentOnly),
+            _ => return None,
+        };
+        Some(format)
+    }
+
+    fn decode(self, bits: u16) -> f64 {
+        let Self {
+            exponent_bits,
+            mantissa_bits,
+            bias,
+            special,
+        } = self;
+        let max_exponent = (1 << exponent_bits) - 1;
+        let max_mantissa = (1 << mantissa_bits) - 1;
+        let exponent = (bits >> mantissa_bits) & max_exponent;
+        let mantissa = bits & max_mantissa;
+        let negative = match special {
+            Special::ExponentOnly => false,
+            _ => bits >> (exponent_bits + mantissa_bits) & 1 != 0,
+        };
+        let nan = match special {
+            Special::Ieee => exponent == max_exponent && mantissa != 0,
+            Special::Finite => exponent == max_exponent && mantissa == max_mantissa,
+            Special::FiniteUnsignedZero => negative && exponent == 0 && mantissa == 0,
+            Special::ExponentOnly => exponent == max_exponent,
+        };
+        if nan {
+            return f64::NAN;
+        }
+        let scale = f64::from(1_u32 << mantissa_bits);
+        let magnitude = match (special, exponent) {
+            (Special::Ieee, exponent) if exponent == max_exponent => f64::INFINITY,
+            (Special::ExponentOnly, exponent) => 2f64.powi(i32::from(exponent) - bias),
+            (_, 0) => f64::from(mantissa) / scale * 2f64.powi(1 - bias),
+            (_, exponent) => {
+                (1.0 + f64::from(mantissa) / scale) * 2f64.powi(i32::from(exponent) - bias)
+            }
+        };
+        if negative { -magnitude } else { magnitude }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::DLDevice;
+
+    fn tensor(dtype: DLDataType, data: *const u8, shape: &[i64], strides: &[i64]) -> DLTensor {
+        DLTensor {
+            data: data.cast_mut().cast(),
+            device: DLDevice::CPU,
+            ndim: shape.len() as i32,
+            dtype,
+            shape: shape.as_ptr().cast_mut(),
+            strides: strides.as_ptr().cast_mut(),
+            byte_offset: 0,
+        }
+    }
+
+    fn show(tensor: &DLTensor, options: PrintOptions) -> String {
+        unsafe { TensorDisplay::new(tensor, None, DlpackFlags::empty()) }
+            .with_options(options)
+            .to_string()
+    }
+
+    #[test]
+    fn prints_nested_rows_aligned_to_the_widest_value() {
+        let data = [1_i32, -20, 3, 400, 5, 6];
+        let shape = [2, 3];
+        let tensor = tensor(DLDataType::I32, data.as_ptr().cast(), &shape, &[3, 1]);
+
+        assert_eq!(
+            show(&tensor, PrintOptions::default()),
+            "[[  1, -20,   3],\n [400,   5,   6]]"
+        );
+    }
+
+    #[test]
+    fn walks_non_compact_strides_in_logical_order() {
+        let data = [1_u8, 2, 3, 4, 5, 6];
+        let shape = [3, 2];
+        let transposed = tensor(DLDataType::U8, data.as_ptr(), &shape, &[1, 3]);
+
+        assert_eq!(
+            show(&transposed, PrintOptions::default()),
+            "[[1, 4],\n [2, 5],\n [3, 6]]"
+        );
+    }
+
+    #[test]
+    fn separates_higher_rank_blocks_with_blank_lines() {
+        let data = [0_i8, 1, 2, 3, 4, 5, 6, 7];
+        let shape = [2, 2, 2];
+        let tensor = tensor(DLDataType::I8, data.as_ptr().cast(), &shape, &[4, 2, 1]);
+
+        assert_eq!(
+            show(&tensor, PrintOptions::default()),
+            "[[[0, 1],\n  [2, 3]],\n\n [[4, 5],\n  [6, 7]]]"
+        );
+    }
+
+    #[test]
+    fn summarizes_long_axes_beyond_the_threshold() {
+        let data: Vec<i64> = (0..20).collect();
+        let shape = [20];
+        let tensor = tensor(DLDataType::I64, data.as_ptr().cast(), &shape, &[1]);
+        let options = PrintOptions {
+            threshold: 10,
+            edge_items: 2,
+            ..PrintOptions::default()
+        };
+
+        assert_eq!(show(&tensor, options), "[ 0,  1, ..., 18, 19]");
+    }
+
+    #[test]
+    fn formats_floats_with_configurable_precision_and_width() {
+        let data = [1.5_f64, f64::NAN, f64::NEG_INFINITY];
+        let shape = [3];
+        let tensor = tensor(DLDataType::F64, data.as_ptr().cast(), &shape, &[1]);
+        let options = PrintOptions {
+            precision: 2,
+            width: Some(6),
+            ..PrintOptions::default()
+        };
+
+        assert_eq!(show(&tensor, options), "[  1.50,    nan,   -inf]");
+    }
+
+    #[test]
+    fn formats_half_bool_and_complex_elements() {
+        let halves = [0x3c00_u16, 0xc000, 0x0001];
+        let shape = [3];
+        let f16 = tensor(DLDataType::F16, halves.as_ptr().cast(), &shape, &[1]);
+        assert_eq!(
+            show(
+                &f16,
+                PrintOptions {
+                    precision: 1,
+                    ..PrintOptions::default()
+                }
+            ),
+            "[ 1.0, -2.0,  0.0]"
+        );
+
+        let bf16 = [0x3fc0_u16];
+        let shape = [1];
+        let bf16 = tensor(DLDataType::BF16, bf16.as_ptr().cast(), &shape, &[1]);
+        assert_eq!(show(&bf16, PrintOptions::default()), "[1.5000]");
+
+        let flags = [1_u8, 0];
+        let shape = [2];
+        let bools = tensor(DLDataType::BOOL, flags.as_ptr(), &shape, &[1]);
+        assert_eq!(show(&bools, PrintOptions::default()), "[ True, False]");
+
+        let complex = [1.0_f32, -2.0];
+        let shape = [1];
+        let c64 = tensor(DLDataType::C64, complex.as_ptr().cast(), &shape, &[1]);
+        assert_eq!(
+            show(
+                &c64,
+                PrintOptions {
+                    precision: 1,
+                    ..PrintOptions::default()
+                }
+            ),
+            "[1.0-2.0j]"
+        );
+    }
+
+    #[test]
+    fn decodes_fp8_elements() {
+        let shape = [4];
+        let show = |dtype, bits: &[u8; 4]| {
+            let tensor = tensor(dtype, bits.as_ptr(), &shape, &[1]);
+            let options = PrintOptions {
+                precision: 3,
+                ..PrintOptions::default()
+            };
+            show(&tensor, options)
+        };
+
+        // 1.0, -448 (the largest finite value), the smallest subnormal, NaN.
+        assert_eq!(
+            show(DLDataType::F8E4M3FN, &[0x38, 0xfe, 0x01, 0x7f]),
+            "[   1.000, -448.000,    0.002,      nan]"
+        );
+        // 1.5, -inf, 57344 (the largest finite value), NaN.
+        assert_eq!(
+            show(DLDataType::F8E5M2, &[0x3e, 0xfc, 0x7b, 0x7d]),
+            "[    1.500,      -inf, 57344.000,       nan]"
+        );
+        // The negative zero pattern is the only NaN in the fnuz formats.
+        assert_eq!(
+            show(DLDataType::F8E4M3FNUZ, &[0x40, 0x80, 0x00, 0xff]),
+            "[   1.000,      nan,    0.000, -240.000]"
+        );
+        assert_eq!(
+            show(DLDataType::F8E8M0FNU, &[0x7f, 0x80, 0x00, 0xff]),
+            "[1.000, 2.000, 0.000,   nan]"
+        );
+    }
+
+    #[test]
+    fn scalars_and_empty_tensors() {
+        let value = 7_u16;
+        let scalar = tensor(DLDataType::U16, (&raw const value).cast(), &[], &[]);
+        assert_eq!(show(&scalar, PrintOptions::default()), "7");
+
+        let shape = [2, 0];
+        let empty = tensor(DLDataType::F32, std::ptr::null(), &shape, &[0, 1]);
+        assert_eq!(show(&empty, PrintOptions::default()), "[]");
+    }
+
+    #[test]
+    fn non_cpu_and_unprintable_dtypes_fall_back_to_metadata() {
+        let data = [0_u8; 4];
+        let shape = [4];
+        let mut cuda = tensor(DLDataType::U8, data.as_ptr(), &shape, &[1]);
+        cuda.device = DLDevice::cuda(1);
+        assert_eq!(show(&cuda, PrintOptions::default()), "uint8[4] cuda:1");
+
+        let packed = tensor(DLDataType::F4E2M1FN, data.as_ptr(), &shape, &[1]);
+        assert_eq!(
+            show(&packed, PrintOptions::default()),
+            "float4_e2m1fn[4] cpu:0"
+        );
+    }
+}